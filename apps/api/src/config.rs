@@ -6,24 +6,42 @@ use sqlx::PgPool;
 use redis::Client;
 use aws_sdk_s3::Client as S3Client;
 use rdkafka::producer::FutureProducer;
+use rdkafka::consumer::StreamConsumer;
 use rdkafka::config::ClientConfig;
 use neo4rs::Graph;
 use std::env;
+use std::path::PathBuf;
 use opensearch::{
     OpenSearch, 
     http::transport::{SingleNodeConnectionPool, TransportBuilder},
     http::Url,
 };
 
+#[derive(Clone)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub from: String,
+}
+
+pub enum StorageConfig {
+    S3(Arc<S3Client>),
+    Local { root: PathBuf, public_url: String },
+}
+
 pub struct Config {
     pub neo4j: Arc<Graph>,
     pub redis: Client,
     pub postgres: PgPool,
     pub kafka_producer: Arc<FutureProducer>,
-    pub s3_client: Arc<S3Client>,
+    pub kafka_consumer: Arc<StreamConsumer>,
+    pub storage: StorageConfig,
     pub opensearch: Arc<OpenSearch>,
     pub jwt_secret: String,
     pub encryption_key: String,
+    pub smtp: Option<SmtpConfig>,
+    pub admin_emails: Vec<String>,
+    pub synonyms_path: String,
 }
 
 impl Config {
@@ -36,13 +54,25 @@ impl Config {
         let redis_url = env::var("REDIS_URL").expect("REDIS_URL missing");
         let postgres_url = env::var("POSTGRES_URL").expect("POSTGRES_URL missing");
         let kafka_url = env::var("KAFKA_URL").expect("KAFKA_URL missing");
-        let region = env::var("AWS_REGION").unwrap_or_else(|_| "us-east-1".to_string());
-        let cred_username = env::var("AWS_ACCESS_KEY_ID").expect("AWS_ACCESS_KEY_ID is missing");
-        let cred_pass = env::var("AWS_SECRET_ACCESS_KEY").expect("AWS_SECRET_ACCESS_KEY is missing");
-        let endpoint = env::var("S3_ENDPOINT_URL").expect("S3_ENDPOINT_URL is missing");
+        let admin_emails = env::var("ADMIN_EMAILS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|e| e.trim().to_lowercase())
+            .filter(|e| !e.is_empty())
+            .collect();
+        let storage_backend = env::var("STORAGE_BACKEND").unwrap_or_else(|_| "s3".to_string());
         let opensearch_url = env::var("OPENSEARCH_URL").expect("OPENSEARCH_URL missing");
+        let synonyms_path = env::var("SEARCH_SYNONYMS_PATH").unwrap_or_else(|_| "./setup/synonyms.txt".to_string());
         let jwt_secret = env::var("JWT_SECRET").expect("JWT_SECRET is missing");
         let encryption_key = env::var("ENCRYPTION_KEY").expect("ENCRYPTION_KEY is missing");
+        let smtp = env::var("SMTP_HOST").ok().map(|host| SmtpConfig {
+            host,
+            port: env::var("SMTP_PORT")
+                .ok()
+                .and_then(|p| p.parse().ok())
+                .unwrap_or(25),
+            from: env::var("SMTP_FROM").unwrap_or_else(|_| "no-reply@dev-academy.local".to_string()),
+        });
 
         let neo4j = Arc::new(
             Graph::new(&neo4j_url, &neo4j_user, &neo4j_pass)
@@ -66,21 +96,27 @@ impl Config {
                 .expect("Failed to create Kafka producer"),
         );
 
-        let aws_conf = aws_sdk_s3::Config::builder()
-        .behavior_version(BehaviorVersion::latest())
-        .region(Region::new(region))
-        .endpoint_url(endpoint)
-        .credentials_provider(Credentials::new(
-            cred_username,
-            cred_pass,
-            None,
-            None,
-            "localstack"
-        ))
-        .force_path_style(true)  
-        .build();
+        let kafka_consumer = Arc::new(
+            ClientConfig::new()
+                .set("bootstrap.servers", &kafka_url)
+                .set("group.id", "api-notifications")
+                .set("enable.auto.commit", "true")
+                .set("auto.offset.reset", "latest")
+                .create()
+                .expect("Failed to create Kafka consumer"),
+        );
 
-        let s3_client = Arc::new(S3Client::from_conf(aws_conf));
+        let storage = match storage_backend.as_str() {
+            "local" => StorageConfig::Local {
+                root: PathBuf::from(
+                    env::var("LOCAL_STORAGE_ROOT").unwrap_or_else(|_| "./storage".to_string()),
+                ),
+                public_url: env::var("PUBLIC_API_URL")
+                    .unwrap_or_else(|_| "http://localhost:9090/api".to_string()),
+            },
+            "s3" => StorageConfig::S3(Arc::new(s3_client_from_env())),
+            other => panic!("Unknown STORAGE_BACKEND {}", other),
+        };
 
                 let opensearch_parsed_url = Url::parse(&opensearch_url)
             .expect("Invalid OpenSearch URL");
@@ -95,13 +131,40 @@ impl Config {
         Self {
             neo4j,
             redis,
-            s3_client,
+            storage,
             postgres,
             kafka_producer,
+            kafka_consumer,
             opensearch,
             jwt_secret,
             encryption_key,
+            smtp,
+            admin_emails,
+            synonyms_path,
         }
     }
 }
 
+
+fn s3_client_from_env() -> S3Client {
+    let region = env::var("AWS_REGION").unwrap_or_else(|_| "us-east-1".to_string());
+    let cred_username = env::var("AWS_ACCESS_KEY_ID").expect("AWS_ACCESS_KEY_ID is missing");
+    let cred_pass = env::var("AWS_SECRET_ACCESS_KEY").expect("AWS_SECRET_ACCESS_KEY is missing");
+    let endpoint = env::var("S3_ENDPOINT_URL").expect("S3_ENDPOINT_URL is missing");
+
+    let aws_conf = aws_sdk_s3::Config::builder()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new(region))
+        .endpoint_url(endpoint)
+        .credentials_provider(Credentials::new(
+            cred_username,
+            cred_pass,
+            None,
+            None,
+            "localstack"
+        ))
+        .force_path_style(true)
+        .build();
+
+    S3Client::from_conf(aws_conf)
+}
@@ -0,0 +1,43 @@
+use serde::Deserialize;
+
+use crate::service::media_gc::DEFAULT_GRACE_PERIOD_HOURS;
+
+fn default_grace_period_hours() -> i64 {
+    DEFAULT_GRACE_PERIOD_HOURS
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MediaReconcileRequest {
+    #[serde(default)]
+    pub delete: bool,
+    #[serde(default = "default_grace_period_hours")]
+    pub grace_period_hours: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateSynonymsRequest {
+    pub synonyms: Vec<String>,
+}
+
+fn default_analytics_days() -> i64 {
+    30
+}
+
+fn default_analytics_limit() -> i64 {
+    20
+}
+
+fn default_min_searches() -> i64 {
+    5
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchAnalyticsQuery {
+    #[serde(default = "default_analytics_days")]
+    pub days: i64,
+    #[serde(default = "default_analytics_limit")]
+    pub limit: i64,
+    /// Only used by the click-through report, where a handful of searches says little.
+    #[serde(default = "default_min_searches")]
+    pub min_searches: i64,
+}
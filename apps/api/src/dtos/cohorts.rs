@@ -0,0 +1,30 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::models::cohort::{Cohort, CohortModuleSchedule};
+
+#[derive(Serialize, Deserialize)]
+pub struct ModuleScheduleRequest {
+    pub module_id: String,
+    pub release_at: NaiveDateTime,
+    pub due_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateCohortRequest {
+    pub course_id: String,
+    pub name: String,
+    pub starts_at: NaiveDateTime,
+    pub ends_at: NaiveDateTime,
+    pub capacity: i32,
+    #[serde(default)]
+    pub schedule: Vec<ModuleScheduleRequest>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CohortDetail {
+    pub cohort: Cohort,
+    pub schedule: Vec<CohortModuleSchedule>,
+    pub active_members: usize,
+    pub waitlisted: usize,
+}
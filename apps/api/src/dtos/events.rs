@@ -7,3 +7,11 @@ pub struct CacheInvalidationEvent {
     pub resource_id: String,
     pub timestamp: i64,
 }
+
+#[derive(Serialize, Deserialize)]
+pub struct LessonCompletedEvent {
+    pub user_id: String,
+    pub course_id: String,
+    pub lesson_id: String,
+    pub timestamp: i64,
+}
@@ -0,0 +1,11 @@
+use serde::Deserialize;
+
+use crate::models::image::ImageFormat;
+
+#[derive(Debug, Deserialize)]
+pub struct ImageQuery {
+    pub width: Option<u32>,
+    pub format: Option<ImageFormat>,
+    #[serde(default)]
+    pub v: Option<String>,
+}
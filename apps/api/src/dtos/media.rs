@@ -0,0 +1,15 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryMode {
+    #[default]
+    Redirect,
+    Proxy,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MediaQuery {
+    #[serde(default)]
+    pub mode: DeliveryMode,
+}
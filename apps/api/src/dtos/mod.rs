@@ -4,3 +4,11 @@ pub mod lessons;
 pub mod events;
 pub mod search;
 pub mod auth;
+pub mod progress;
+pub mod cohorts;
+pub mod notifications;
+pub mod uploads;
+pub mod media;
+pub mod storage;
+pub mod images;
+pub mod admin;
@@ -0,0 +1,23 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct NotificationListQuery {
+    #[serde(default)]
+    pub unread_only: bool,
+    #[serde(default)]
+    pub from: i64,
+    #[serde(default = "default_size")]
+    pub size: i64,
+}
+
+fn default_size() -> i64 {
+    20
+}
+
+pub struct NewNotification {
+    pub kind: String,
+    pub title: String,
+    pub body: String,
+    pub resource_id: Option<String>,
+    pub dedup_key: Option<String>,
+}
@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use neo4rs::{BoltList, BoltMap, BoltType, Error, Node, Row};
+use neo4rs::{Error, Node, Row};
 use crate::{error::ApiError, models::programe::{Course, Lesson, Module}, traits::from_node::FromNode};
 
 
@@ -78,7 +78,7 @@ impl FromNode for ProgramDetail {
 
             let lessons: Vec<Lesson> = module_lessons_nodes
                 .iter()
-                .map(|n| Lesson::from_node(n))
+                .map(Lesson::from_node)
                 .collect::<Result<Vec<_>, _>>()?
                 .into_iter()
                 .filter(|lesson| lesson.order == module_order)
@@ -107,7 +107,7 @@ impl FromNode for ModuleWithLessons {
             .map_err(|_| ApiError::Neo4j(Error::ConversionError))?;
         let lessons: Vec<Lesson> = lessons_nodes
             .iter()
-            .map(|n| Lesson::from_node(n))
+            .map(Lesson::from_node)
             .collect::<Result<_, _>>()?;
 
         Ok(Self {
@@ -116,3 +116,35 @@ impl FromNode for ModuleWithLessons {
         })
     }
 }
+
+impl TryFrom<Row> for ModuleWithLessons {
+    type Error = ApiError;
+
+    fn try_from(row: Row) -> Result<Self, Self::Error> {
+        let module_node: Node = row.get("m")
+            .map_err(|_| ApiError::Neo4j(Error::ConversionError))?;
+        let module = Module::from_node(&module_node)?;
+
+        let lessons_nodes: Vec<Node> = row.get("lessons")
+            .map_err(|_| ApiError::Neo4j(Error::ConversionError))?;
+        let lessons: Vec<Lesson> = lessons_nodes
+            .iter()
+            .map(Lesson::from_node)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            module,
+            lessons,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RelatedQuery {
+    #[serde(default = "default_related_limit")]
+    pub limit: usize,
+}
+
+fn default_related_limit() -> usize {
+    6
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::programe::Lesson;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NextLesson {
+    pub module_id: String,
+    pub module_title: String,
+    pub lesson: Lesson,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContinueLearningItem {
+    pub course_id: String,
+    pub course_title: String,
+    pub next_lesson: Option<NextLesson>,
+    pub completed_lessons: usize,
+    pub total_lessons: usize,
+    pub remaining_minutes: i32,
+    pub blocked: bool,
+}
@@ -1,15 +1,38 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::models::image::CoverVariant;
+use crate::models::programe::Course;
+use crate::models::search::{DocumentMatch, FilterCondition, HighlightOptions, ModuleHit, SearchFacets};
 
 
 #[derive(Debug, Deserialize)]
 pub struct SearchRequest {
+    #[serde(default)]
     pub query: String,
+    /// Query-language form, e.g. `level:beginner duration:<60 "async rust" -draft`; takes precedence over `query`.
+    #[serde(default)]
+    pub q: Option<String>,
     #[serde(default = "default_fields")]
     pub fields: Vec<String>,
     #[serde(default)]
     pub from: i64,
     #[serde(default = "default_size")]
     pub size: i64,
+    /// `next_cursor` from the previous page; replaces `from`.
+    #[serde(default)]
+    pub cursor: Option<String>,
+    #[serde(default)]
+    pub point_in_time: bool,
+    #[serde(default)]
+    pub highlight: HighlightOptions,
+    /// Ranks by the signed-in learner's level, interests and progress; `false` gives plain relevance.
+    #[serde(default = "default_personalize")]
+    pub personalize: bool,
+}
+
+fn default_personalize() -> bool {
+    true
 }
 
 fn default_fields() -> Vec<String> {
@@ -24,6 +47,17 @@ fn default_size() -> i64 {
     10
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SuggestQuery {
+    pub q: String,
+    #[serde(default = "default_suggest_limit")]
+    pub limit: i64,
+}
+
+fn default_suggest_limit() -> i64 {
+    5
+}
+
 #[derive(Debug, Deserialize)]
 pub struct FilterRequest {
     #[serde(default)]
@@ -37,9 +71,29 @@ pub struct FilterRequest {
     #[serde(default)]
     pub title_match: Option<String>,
     #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub level: Option<String>,
+    #[serde(default)]
+    pub instructor: Option<String>,
+    #[serde(default)]
+    pub min_rating: Option<f64>,
+    #[serde(default)]
+    pub max_rating: Option<f64>,
+    #[serde(default)]
+    pub featured: Option<bool>,
+    /// An arbitrary condition tree combined with the fields above.
+    #[serde(default)]
+    pub filter: Option<FilterCondition>,
+    #[serde(default)]
     pub from: i64,
     #[serde(default = "default_size")]
     pub size: i64,
+    /// `next_cursor` from the previous page; replaces `from`.
+    #[serde(default)]
+    pub cursor: Option<String>,
+    #[serde(default)]
+    pub point_in_time: bool,
     #[serde(default)]
     pub sort_by: Option<String>,
     #[serde(default)]
@@ -53,6 +107,22 @@ pub struct SearchResultResponse<T> {
     pub from: i64,
     pub size: i64,
     pub source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub facets: Option<SearchFacets>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    /// Identifies this search when reporting which result was clicked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search_id: Option<String>,
+    #[serde(default)]
+    pub personalized: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchClickRequest {
+    pub result_id: String,
+    #[serde(default)]
+    pub position: Option<i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -61,11 +131,53 @@ pub struct CourseSearchResult {
     pub title: String,
     pub description: String,
     pub status: String,
+    #[serde(default)]
+    pub category: String,
+    #[serde(default)]
+    pub level: String,
+    #[serde(default)]
+    pub rating: f32,
+    #[serde(default)]
+    pub instructor: String,
+    #[serde(default)]
+    pub featured: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cover: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cover_variants: Vec<CoverVariant>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub prerequisites: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub documents: Option<Vec<String>>,
     pub total_duration_minutes: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matched_in: Option<DocumentMatch>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub matched_modules: Vec<ModuleHit>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub highlights: HashMap<String, Vec<String>>,
+}
+
+impl From<Course> for CourseSearchResult {
+    fn from(course: Course) -> Self {
+        Self {
+            id: course.id,
+            title: course.title,
+            description: course.description,
+            status: course.status,
+            category: course.category,
+            level: course.level,
+            rating: course.rating,
+            instructor: course.instructor,
+            featured: course.featured,
+            cover: (!course.cover.is_empty()).then_some(course.cover),
+            cover_variants: course.cover_variants,
+            prerequisites: Some(course.prerequisites),
+            documents: Some(course.documents),
+            total_duration_minutes: course.total_duration_minutes,
+            matched_in: None,
+            matched_modules: Vec::new(),
+            highlights: HashMap::new(),
+        }
+    }
 }
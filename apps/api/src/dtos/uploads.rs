@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::upload::{MediaKind, UploadSession};
+
+#[derive(Debug, Deserialize)]
+pub struct UploadIntentRequest {
+    pub kind: MediaKind,
+    pub target_id: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UploadIntentResponse {
+    pub upload_id: String,
+    pub upload_url: String,
+    pub method: String,
+    pub headers: serde_json::Value,
+    pub bucket: String,
+    pub key: String,
+    pub expires_in_seconds: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UploadedPartResponse {
+    pub part_number: i32,
+    pub e_tag: String,
+    pub size: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UploadSessionResponse {
+    pub session: UploadSession,
+    pub part_count: i64,
+    pub uploaded_parts: Vec<UploadedPartResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PresignedPartResponse {
+    pub part_number: i32,
+    pub upload_url: String,
+    pub method: String,
+    pub expires_in_seconds: u64,
+}
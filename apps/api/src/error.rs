@@ -14,9 +14,18 @@ pub enum ApiError {
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
     
+    #[error("Storage error: {0}")]
+    Storage(String),
+    
     #[error("Not found: {0}")]
     NotFound(String),
     
+    #[error("Bad request: {0}")]
+    BadRequest(String),
+    
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+    
     #[error("Internal error: {0}")]
     Internal(String),
 }
@@ -27,6 +36,8 @@ impl actix_web::ResponseError for ApiError {
         
         let status = match self {
             ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         };
         
@@ -0,0 +1,230 @@
+use actix_web::{get, post, put, web, HttpRequest, HttpResponse};
+use chrono::Duration;
+use crate::config::Config;
+use crate::dtos::admin::{MediaReconcileRequest, SearchAnalyticsQuery, UpdateSynonymsRequest};
+use crate::handlers::auth::current_admin;
+use crate::service::media_gc::{reconcile_media, ReconcileOptions};
+use crate::service::index_mappings::{load_synonyms, save_synonyms, validate_synonym};
+use crate::service::programs_reindex::{programs_index_status, reindex_programs};
+use crate::service::search_analytics;
+use crate::service::{content_index, index_queue, AppServices};
+use crate::workers::documents::reindex_all_documents;
+use crate::traits::redis_trait::RedisCache;
+use crate::error::ApiError;
+
+#[post("/media/reconcile")]
+pub async fn reconcile_media_objects(
+    req: HttpRequest,
+    body: web::Json<MediaReconcileRequest>,
+    config: web::Data<Config>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    current_admin(&req, &services, &config).await?;
+
+    if body.grace_period_hours < 0 {
+        return Err(ApiError::BadRequest("grace_period_hours must not be negative".to_string()));
+    }
+
+    let options = ReconcileOptions {
+        delete: body.delete,
+        grace_period: Duration::hours(body.grace_period_hours),
+    };
+
+    let report = reconcile_media(services.storage.as_ref(), &services.neo4j, &options).await?;
+
+    Ok(HttpResponse::Ok().json(report))
+}
+
+#[post("/search/documents/reindex")]
+pub async fn reindex_documents(
+    req: HttpRequest,
+    config: web::Data<Config>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    current_admin(&req, &services, &config).await?;
+
+    actix_web::rt::spawn(reindex_all_documents(services.clone()));
+
+    Ok(HttpResponse::Accepted().json(serde_json::json!({
+        "message": "Document reindex started"
+    })))
+}
+
+#[post("/search/content/reindex")]
+pub async fn reindex_content(
+    req: HttpRequest,
+    config: web::Data<Config>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    current_admin(&req, &services, &config).await?;
+
+    let indexed = content_index::sync_all_content(&services).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "indexed": indexed
+    })))
+}
+
+#[get("/search/programs/status")]
+pub async fn programs_search_index_status(
+    req: HttpRequest,
+    config: web::Data<Config>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    current_admin(&req, &services, &config).await?;
+
+    let status = programs_index_status(&services.opensearch).await?;
+
+    Ok(HttpResponse::Ok().json(status))
+}
+
+#[get("/search/index-retries")]
+pub async fn search_index_retries(
+    req: HttpRequest,
+    config: web::Data<Config>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    current_admin(&req, &services, &config).await?;
+
+    let retries = index_queue::pending(&services).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "pending": retries.len(),
+        "retries": retries
+    })))
+}
+
+#[post("/search/programs/reindex")]
+pub async fn reindex_programs_search(
+    req: HttpRequest,
+    config: web::Data<Config>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    current_admin(&req, &services, &config).await?;
+
+    let report = reindex_programs(&services.opensearch, &services.neo4j).await?;
+
+    Ok(HttpResponse::Ok().json(report))
+}
+
+#[get("/search/synonyms")]
+pub async fn get_search_synonyms(
+    req: HttpRequest,
+    config: web::Data<Config>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    current_admin(&req, &services, &config).await?;
+
+    let synonyms = load_synonyms(services.opensearch.synonyms_path()).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "synonyms": synonyms
+    })))
+}
+
+/// Synonyms are baked into the index's search analyzer, so applying them means
+/// rebuilding the programs index and swapping the alias onto it.
+#[put("/search/synonyms")]
+pub async fn update_search_synonyms(
+    req: HttpRequest,
+    body: web::Json<UpdateSynonymsRequest>,
+    config: web::Data<Config>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    current_admin(&req, &services, &config).await?;
+
+    let synonyms: Vec<String> = body.synonyms
+        .iter()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect();
+    synonyms
+        .iter()
+        .try_for_each(|line| validate_synonym(line))
+        .map_err(ApiError::BadRequest)?;
+
+    save_synonyms(services.opensearch.synonyms_path(), &synonyms).await?;
+    let report = reindex_programs(&services.opensearch, &services.neo4j).await?;
+    services.cache.delete_all("search:*").await?;
+    services.cache.delete_all("filter:programs:*").await?;
+    services.cache.delete_all("advanced:programs:*").await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "synonyms": synonyms,
+        "reindex": report
+    })))
+}
+
+fn validate_analytics_query(query: &SearchAnalyticsQuery) -> Result<(), ApiError> {
+    if !(1..=365).contains(&query.days) {
+        return Err(ApiError::BadRequest("days must be between 1 and 365".to_string()));
+    }
+    if !(1..=200).contains(&query.limit) {
+        return Err(ApiError::BadRequest("limit must be between 1 and 200".to_string()));
+    }
+    if query.min_searches < 1 {
+        return Err(ApiError::BadRequest("min_searches must be at least 1".to_string()));
+    }
+    Ok(())
+}
+
+#[get("/search/analytics/top-queries")]
+pub async fn top_search_queries(
+    req: HttpRequest,
+    query: web::Query<SearchAnalyticsQuery>,
+    config: web::Data<Config>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    current_admin(&req, &services, &config).await?;
+    validate_analytics_query(&query)?;
+
+    let queries = search_analytics::top_queries(&services, query.days, query.limit).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "days": query.days,
+        "queries": queries
+    })))
+}
+
+#[get("/search/analytics/zero-results")]
+pub async fn zero_result_search_queries(
+    req: HttpRequest,
+    query: web::Query<SearchAnalyticsQuery>,
+    config: web::Data<Config>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    current_admin(&req, &services, &config).await?;
+    validate_analytics_query(&query)?;
+
+    let queries = search_analytics::zero_result_queries(&services, query.days, query.limit).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "days": query.days,
+        "queries": queries
+    })))
+}
+
+#[get("/search/analytics/click-through")]
+pub async fn search_click_through(
+    req: HttpRequest,
+    query: web::Query<SearchAnalyticsQuery>,
+    config: web::Data<Config>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    current_admin(&req, &services, &config).await?;
+    validate_analytics_query(&query)?;
+
+    let queries = search_analytics::click_through_rates(
+        &services,
+        query.days,
+        query.limit,
+        query.min_searches,
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "days": query.days,
+        "min_searches": query.min_searches,
+        "queries": queries
+    })))
+}
@@ -4,12 +4,13 @@ use sha2::Sha256;
 use subtle::ConstantTimeEq;
 use actix_web::{cookie::{Cookie, SameSite}, get, post, web, HttpMessage, HttpRequest, HttpResponse};
 use aes_gcm::aead::{rand_core::RngCore, OsRng};
+use crate::config::Config;
 use crate::{dtos::auth::{AuthResponse, Claims, LoginPasskeyRequest, LoginPasswordRequest, RegisterPasskeyRequest, SignupRequest}, error::ApiError, models::auth::{Passkey, User}, service::{jwt_service::{ITER, KEY_LEN, SALT_LEN}, AppServices}};
 
 
 pub fn hash_password(password: &[u8]) -> (Vec<u8>, Vec<u8>) {
     let mut salt = vec![0u8; SALT_LEN];
-    let _ = OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut salt);
 
     let mut hash = vec![0u8; KEY_LEN];
     let iterations = NonZeroU32::new(ITER).expect("iterations must be non-zero");
@@ -28,6 +29,55 @@ pub fn verify_password(password: &[u8], salt: &[u8], expected_hash: &[u8]) -> bo
     computed.ct_eq(expected_hash).into()
 }
 
+pub async fn current_user(req: &HttpRequest, services: &AppServices) -> Result<User, ApiError> {
+    let claims = req
+        .extensions()
+        .get::<Claims>()
+        .cloned()
+        .ok_or_else(|| ApiError::Internal("Missing auth claims".into()))?;
+
+    services
+        .postgress
+        .query("SELECT * FROM users WHERE email = $1")
+        .bind(&claims.email)
+        .fetch_optional()
+        .await?
+        .ok_or_else(|| ApiError::NotFound("User not found".to_string()))
+}
+
+/// The signed-in user on public routes, which skip the auth middleware;
+/// a missing or invalid token means an anonymous request rather than an error.
+pub async fn optional_user(req: &HttpRequest, services: &AppServices) -> Result<Option<User>, ApiError> {
+    let Some(cookie) = req.cookie("auth_token") else {
+        return Ok(None);
+    };
+
+    let claims = match services.jwt_service
+        .decrypt_token(cookie.value())
+        .and_then(|token| services.jwt_service.verify_jwt(&token))
+    {
+        Ok(claims) => claims,
+        Err(_) => return Ok(None),
+    };
+
+    services
+        .postgress
+        .query("SELECT * FROM users WHERE email = $1")
+        .bind(&claims.email)
+        .fetch_optional()
+        .await
+}
+
+pub async fn current_admin(req: &HttpRequest, services: &AppServices, config: &Config) -> Result<User, ApiError> {
+    let user = current_user(req, services).await?;
+
+    if !config.admin_emails.contains(&user.email.to_lowercase()) {
+        return Err(ApiError::Forbidden("Admin access required".to_string()));
+    }
+
+    Ok(user)
+}
+
 #[post("/signup/password")]
 pub async fn signup(
     req: web::Json<SignupRequest>,
@@ -0,0 +1,478 @@
+use std::collections::{HashMap, HashSet};
+use actix_web::{delete, get, post, web, HttpRequest, HttpResponse};
+use chrono::{NaiveDateTime, Utc};
+use sqlx::types::Uuid;
+use crate::dtos::cohorts::{CohortDetail, CreateCohortRequest};
+use crate::handlers::auth::current_user;
+use crate::models::cohort::{Cohort, CohortMember, CohortModuleSchedule};
+use crate::models::programe::{Course, Module};
+use crate::models::progress::Enrollment;
+use crate::service::postgres_service::PgTransaction;
+use crate::service::AppServices;
+use crate::traits::redis_trait::RedisCache;
+use crate::error::ApiError;
+
+/// Modules of the course a learner may not open yet: those whose release date has not
+/// passed in their cohort, or every scheduled module while they are only waitlisted.
+pub async fn locked_module_ids(
+    services: &AppServices,
+    user_id: Uuid,
+    course_id: &str,
+) -> Result<HashSet<String>, ApiError> {
+    let pending: Vec<CohortModuleSchedule> = services
+        .postgress
+        .query(
+            r#"
+            SELECT s.*
+            FROM cohort_module_schedules s
+            JOIN cohorts c ON c.id = s.cohort_id
+            JOIN cohort_members m ON m.cohort_id = s.cohort_id
+            WHERE m.user_id = $1::uuid
+              AND c.course_id = $2
+              AND (m.status <> 'active' OR s.release_at > NOW() AT TIME ZONE 'UTC')
+            "#,
+        )
+        .bind(user_id)
+        .bind(course_id)
+        .fetch_all()
+        .await?;
+
+    Ok(pending.into_iter().map(|s| s.module_id).collect())
+}
+
+async fn cohort_detail(services: &AppServices, cohort_id: &str) -> Result<CohortDetail, ApiError> {
+    let cohort: Cohort = services
+        .postgress
+        .query("SELECT * FROM cohorts WHERE id = $1::uuid")
+        .bind(cohort_id)
+        .fetch_optional()
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Cohort {} not found", cohort_id)))?;
+
+    let schedule: Vec<CohortModuleSchedule> = services
+        .postgress
+        .query("SELECT * FROM cohort_module_schedules WHERE cohort_id = $1::uuid ORDER BY release_at")
+        .bind(cohort.id)
+        .fetch_all()
+        .await?;
+
+    let members: Vec<CohortMember> = services
+        .postgress
+        .query("SELECT * FROM cohort_members WHERE cohort_id = $1::uuid")
+        .bind(cohort.id)
+        .fetch_all()
+        .await?;
+
+    let active_members = members.iter().filter(|m| m.status == "active").count();
+
+    Ok(CohortDetail {
+        cohort,
+        schedule,
+        active_members,
+        waitlisted: members.len() - active_members,
+    })
+}
+
+#[post("/cohorts")]
+pub async fn create_cohort(
+    req: web::Json<CreateCohortRequest>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    if req.ends_at <= req.starts_at {
+        return Err(ApiError::BadRequest("ends_at must be after starts_at".to_string()));
+    }
+    if req.capacity <= 0 {
+        return Err(ApiError::BadRequest("capacity must be positive".to_string()));
+    }
+
+    let modules: Vec<Module> = services.neo4j
+        .query_nodes("MATCH (c:Course {id: $course_id})-[:HAS_MODULE]->(m:Module) RETURN m")
+        .param("course_id", req.course_id.clone())
+        .fetch_key("m")
+        .fetch()
+        .await?;
+
+    let module_ids: HashSet<&str> = modules.iter().map(|m| m.id.as_str()).collect();
+    for entry in &req.schedule {
+        if !module_ids.contains(entry.module_id.as_str()) {
+            return Err(ApiError::BadRequest(format!(
+                "Module {} does not belong to course {}",
+                entry.module_id, req.course_id
+            )));
+        }
+        if matches!(entry.due_at, Some(due) if due < entry.release_at) {
+            return Err(ApiError::BadRequest(format!(
+                "Module {} is due before it is released",
+                entry.module_id
+            )));
+        }
+    }
+
+    let mut tx = services.postgress.begin().await?;
+
+    let cohort: Cohort = tx
+        .query(
+            r#"
+            INSERT INTO cohorts (course_id, name, starts_at, ends_at, capacity)
+            VALUES ($1, $2, $3::timestamp, $4::timestamp, $5::integer)
+            RETURNING *
+            "#,
+        )
+        .bind(&req.course_id)
+        .bind(&req.name)
+        .bind(req.starts_at)
+        .bind(req.ends_at)
+        .bind(req.capacity)
+        .fetch_one()
+        .await?;
+
+    for entry in &req.schedule {
+        let _: CohortModuleSchedule = tx
+            .query(
+                r#"
+                INSERT INTO cohort_module_schedules (cohort_id, module_id, release_at, due_at)
+                VALUES ($1::uuid, $2, $3::timestamp, NULLIF($4, '')::timestamp)
+                RETURNING *
+                "#,
+            )
+            .bind(cohort.id)
+            .bind(&entry.module_id)
+            .bind(entry.release_at)
+            .bind(entry.due_at.map(|d| d.to_string()).unwrap_or_default())
+            .fetch_one()
+            .await?;
+    }
+
+    tx.commit().await?;
+
+    services.kafka.publish_cache_invalidation("cohort_created", &cohort.id.to_string()).await?;
+
+    Ok(HttpResponse::Created().json(cohort_detail(&services, &cohort.id.to_string()).await?))
+}
+
+#[get("/programs/{id}/cohorts")]
+pub async fn get_course_cohorts(
+    path: web::Path<String>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let course_id = path.into_inner();
+
+    let cohorts: Vec<Cohort> = services
+        .postgress
+        .query("SELECT * FROM cohorts WHERE course_id = $1 ORDER BY starts_at")
+        .bind(&course_id)
+        .fetch_all()
+        .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "cohorts": cohorts
+    })))
+}
+
+#[get("/cohorts/{id}")]
+pub async fn get_cohort_by_id(
+    path: web::Path<String>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let cohort_id = parse_cohort_id(&path.into_inner())?;
+    Ok(HttpResponse::Ok().json(cohort_detail(&services, &cohort_id.to_string()).await?))
+}
+
+fn parse_cohort_id(cohort_id: &str) -> Result<Uuid, ApiError> {
+    Uuid::parse_str(cohort_id).map_err(|_| ApiError::BadRequest(format!("Invalid cohort id {}", cohort_id)))
+}
+
+/// Locks the cohort row so concurrent joins and leaves see each other's seat counts.
+async fn lock_cohort(tx: &mut PgTransaction, cohort_id: Uuid) -> Result<Cohort, ApiError> {
+    tx.query("SELECT * FROM cohorts WHERE id = $1::uuid FOR UPDATE")
+        .bind(cohort_id)
+        .fetch_optional()
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Cohort {} not found", cohort_id)))
+}
+
+/// Only members holding a seat are enrolled; the waitlist gets its enrollment on promotion.
+async fn enroll_member(tx: &mut PgTransaction, user_id: Uuid, course_id: &str) -> Result<Enrollment, ApiError> {
+    tx.query(
+        r#"
+        INSERT INTO enrollments (user_id, course_id)
+        VALUES ($1::uuid, $2)
+        ON CONFLICT (user_id, course_id) DO UPDATE SET enrolled_at = enrollments.enrolled_at
+        RETURNING *
+        "#,
+    )
+    .bind(user_id)
+    .bind(course_id)
+    .fetch_one()
+    .await
+}
+
+#[post("/cohorts/{id}/join")]
+pub async fn join_cohort(
+    req: HttpRequest,
+    path: web::Path<String>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let cohort_id = parse_cohort_id(&path.into_inner())?;
+    let user = current_user(&req, &services).await?;
+
+    let mut tx = services.postgress.begin().await?;
+    let cohort = lock_cohort(&mut tx, cohort_id).await?;
+
+    let member: CohortMember = tx
+        .query(
+            r#"
+            INSERT INTO cohort_members (cohort_id, user_id, status)
+            SELECT c.id, $2::uuid,
+                CASE WHEN (
+                    SELECT COUNT(*) FROM cohort_members
+                    WHERE cohort_id = c.id AND status = 'active'
+                ) < c.capacity THEN 'active' ELSE 'waitlisted' END
+            FROM cohorts c
+            WHERE c.id = $1::uuid
+            ON CONFLICT (cohort_id, user_id) DO UPDATE SET status = cohort_members.status
+            RETURNING *
+            "#,
+        )
+        .bind(cohort.id)
+        .bind(user.id)
+        .fetch_one()
+        .await?;
+
+    if member.status == "active" {
+        enroll_member(&mut tx, user.id, &cohort.course_id).await?;
+    }
+
+    tx.commit().await?;
+
+    services.cache.delete_all(&format!("progress:{}:*", user.id)).await?;
+
+    Ok(HttpResponse::Created().json(member))
+}
+
+#[delete("/cohorts/{id}/leave")]
+pub async fn leave_cohort(
+    req: HttpRequest,
+    path: web::Path<String>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let cohort_id = parse_cohort_id(&path.into_inner())?;
+    let user = current_user(&req, &services).await?;
+
+    let mut tx = services.postgress.begin().await?;
+    let cohort = lock_cohort(&mut tx, cohort_id).await?;
+
+    let removed: Option<CohortMember> = tx
+        .query("DELETE FROM cohort_members WHERE cohort_id = $1::uuid AND user_id = $2::uuid RETURNING *")
+        .bind(cohort.id)
+        .bind(user.id)
+        .fetch_optional()
+        .await?;
+
+    if removed.is_none() {
+        return Err(ApiError::NotFound("Not a member of this cohort".to_string()));
+    }
+
+    let promoted: Option<CohortMember> = tx
+        .query(
+            r#"
+            UPDATE cohort_members SET status = 'active'
+            WHERE id = (
+                SELECT id FROM cohort_members
+                WHERE cohort_id = $1::uuid AND status = 'waitlisted'
+                ORDER BY joined_at
+                LIMIT 1
+            )
+            AND (
+                SELECT COUNT(*) FROM cohort_members
+                WHERE cohort_id = $1::uuid AND status = 'active'
+            ) < (SELECT capacity FROM cohorts WHERE id = $1::uuid)
+            RETURNING *
+            "#,
+        )
+        .bind(cohort.id)
+        .fetch_optional()
+        .await?;
+
+    if let Some(member) = &promoted {
+        enroll_member(&mut tx, member.user_id, &cohort.course_id).await?;
+    }
+
+    tx.commit().await?;
+
+    services.cache.delete_all(&format!("progress:{}:*", user.id)).await?;
+    if let Some(member) = &promoted {
+        services.cache.delete_all(&format!("progress:{}:*", member.user_id)).await?;
+    }
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "Left cohort",
+        "promoted": promoted.map(|m| m.user_id)
+    })))
+}
+
+fn ics_escape(text: &str) -> String {
+    text.replace("\r\n", "\n")
+        .replace('\r', "\n")
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn ics_date(date: &NaiveDateTime) -> String {
+    date.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn ics_fold(line: &str) -> String {
+    let mut out = String::new();
+    let mut width = 0;
+
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+
+    out
+}
+
+fn ics_event(uid: &str, at: &NaiveDateTime, summary: &str, description: &str) -> Vec<String> {
+    vec![
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}@dev-academy", uid),
+        format!("DTSTAMP:{}", ics_date(&Utc::now().naive_utc())),
+        format!("DTSTART:{}", ics_date(at)),
+        format!("SUMMARY:{}", ics_escape(summary)),
+        format!("DESCRIPTION:{}", ics_escape(description)),
+        "END:VEVENT".to_string(),
+    ]
+}
+
+#[get("/calendar.ics")]
+pub async fn cohort_calendar(
+    req: HttpRequest,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let user = current_user(&req, &services).await?;
+
+    let cohorts: Vec<Cohort> = services
+        .postgress
+        .query(
+            r#"
+            SELECT c.*
+            FROM cohorts c
+            JOIN cohort_members m ON m.cohort_id = c.id
+            WHERE m.user_id = $1::uuid AND m.status = 'active'
+            "#,
+        )
+        .bind(user.id)
+        .fetch_all()
+        .await?;
+
+    let schedules: Vec<CohortModuleSchedule> = services
+        .postgress
+        .query(
+            r#"
+            SELECT s.*
+            FROM cohort_module_schedules s
+            JOIN cohort_members m ON m.cohort_id = s.cohort_id
+            WHERE m.user_id = $1::uuid AND m.status = 'active'
+            ORDER BY s.release_at
+            "#,
+        )
+        .bind(user.id)
+        .fetch_all()
+        .await?;
+
+    let course_ids: Vec<String> = cohorts.iter().map(|c| c.course_id.clone()).collect();
+    let courses: Vec<Course> = services.neo4j
+        .query_nodes("MATCH (c:Course) WHERE c.id IN $ids RETURN c")
+        .param("ids", course_ids)
+        .fetch_key("c")
+        .fetch()
+        .await?;
+    let course_titles: HashMap<String, String> = courses
+        .into_iter()
+        .map(|c| (c.id, c.title))
+        .collect();
+
+    let module_ids: Vec<String> = schedules.iter().map(|s| s.module_id.clone()).collect();
+    let modules: Vec<Module> = services.neo4j
+        .query_nodes("MATCH (m:Module) WHERE m.id IN $ids RETURN m")
+        .param("ids", module_ids)
+        .fetch_key("m")
+        .fetch()
+        .await?;
+    let module_titles: HashMap<String, String> = modules
+        .into_iter()
+        .map(|m| (m.id, m.title))
+        .collect();
+
+    let cohorts_by_id: HashMap<Uuid, &Cohort> = cohorts.iter().map(|c| (c.id, c)).collect();
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Dev Academy//Cohorts//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+    ];
+
+    for cohort in &cohorts {
+        let course_title = course_titles.get(&cohort.course_id).cloned().unwrap_or_default();
+        lines.extend(ics_event(
+            &format!("{}-start", cohort.id),
+            &cohort.starts_at,
+            &format!("{} starts", cohort.name),
+            &course_title,
+        ));
+        lines.extend(ics_event(
+            &format!("{}-end", cohort.id),
+            &cohort.ends_at,
+            &format!("{} ends", cohort.name),
+            &course_title,
+        ));
+    }
+
+    for schedule in &schedules {
+        let Some(cohort) = cohorts_by_id.get(&schedule.cohort_id) else {
+            continue;
+        };
+        let module_title = module_titles
+            .get(&schedule.module_id)
+            .cloned()
+            .unwrap_or_else(|| schedule.module_id.clone());
+
+        lines.extend(ics_event(
+            &format!("{}-release", schedule.id),
+            &schedule.release_at,
+            &format!("{} released", module_title),
+            &cohort.name,
+        ));
+
+        if let Some(due_at) = &schedule.due_at {
+            lines.extend(ics_event(
+                &format!("{}-due", schedule.id),
+                due_at,
+                &format!("{} due", module_title),
+                &cohort.name,
+            ));
+        }
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    let body = lines
+        .iter()
+        .map(|l| ics_fold(l))
+        .collect::<Vec<_>>()
+        .join("\r\n");
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/calendar; charset=utf-8")
+        .body(body + "\r\n"))
+}
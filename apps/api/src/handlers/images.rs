@@ -0,0 +1,80 @@
+use actix_web::{get, http::header, web, HttpResponse};
+use crate::dtos::images::ImageQuery;
+use crate::models::image::{cover_key, is_variant_key, pinned_variant_url, ImageFormat, COVER_BUCKET, VARIANT_WIDTHS};
+use crate::service::{image_variants, AppServices};
+use crate::error::ApiError;
+
+/// Pinned variant URLs name one version of the original, so they never change.
+const VARIANT_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+/// Originals and unpinned variant URLs follow the original, which can be replaced.
+const ORIGINAL_CACHE_CONTROL: &str = "public, max-age=300";
+
+/// Renders all cover variants in the background so the upload request is not held up.
+pub fn schedule_cover_variants(services: &web::Data<AppServices>, cover: &str) {
+    let Some(key) = cover_key(cover).map(|k| k.to_string()) else {
+        return;
+    };
+    let services = services.clone();
+
+    actix_web::rt::spawn(async move {
+        if let Err(e) = image_variants::generate_cover_variants(services.storage.as_ref(), &key).await {
+            eprintln!("⚠ Could not generate variants for cover {}: {}", key, e);
+        }
+    });
+}
+
+#[get("/{key:.*}")]
+pub async fn get_image(
+    path: web::Path<String>,
+    query: web::Query<ImageQuery>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let original_key = path.into_inner();
+    if is_variant_key(&original_key) {
+        return Err(ApiError::NotFound("Image not found".to_string()));
+    }
+
+    let (key, cache_control) = match (query.width, query.format) {
+        (None, None) => (original_key, ORIGINAL_CACHE_CONTROL),
+        (width, format) => {
+            let width = width.unwrap_or(VARIANT_WIDTHS[VARIANT_WIDTHS.len() - 1]);
+            if !VARIANT_WIDTHS.contains(&width) {
+                return Err(ApiError::BadRequest(format!(
+                    "width must be one of {:?}",
+                    VARIANT_WIDTHS
+                )));
+            }
+
+            let format = format.unwrap_or(ImageFormat::Webp);
+            let version = image_variants::original_version(services.storage.as_ref(), &original_key).await?;
+
+            if query.v.as_deref() != Some(version.as_str()) {
+                return Ok(HttpResponse::Found()
+                    .insert_header((header::LOCATION, pinned_variant_url(&original_key, &version, width, format)))
+                    .insert_header((header::CACHE_CONTROL, ORIGINAL_CACHE_CONTROL))
+                    .finish());
+            }
+
+            let key = image_variants::ensure_variant(
+                services.storage.as_ref(),
+                &original_key,
+                &version,
+                width,
+                format,
+            )
+            .await?;
+            (key, VARIANT_CACHE_CONTROL)
+        }
+    };
+
+    let object = services.storage.get(COVER_BUCKET, &key, None).await?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header((
+            header::CONTENT_TYPE,
+            object.content_type.unwrap_or_else(|| "application/octet-stream".to_string()),
+        ))
+        .insert_header((header::CACHE_CONTROL, cache_control))
+        .no_chunking(object.content_length as u64)
+        .streaming(object.body))
+}
@@ -1,8 +1,11 @@
 use actix_web::web;
-use actix_web::{delete, get, post, put, HttpResponse};
+use actix_web::{delete, get, post, put, HttpRequest, HttpResponse};
+use crate::handlers::auth::optional_user;
+use crate::handlers::progress::record_lesson_completion;
 use crate::dtos::lessons::{CreateLessonRequest, UpdateLessonRequest};
+use crate::models::index_retry::IndexTarget;
 use crate::models::programe::Lesson;
-use crate::service::AppServices;
+use crate::service::{index_queue, AppServices};
 use crate::traits::redis_trait::RedisCache;
 use crate::error::ApiError;
 
@@ -78,6 +81,8 @@ pub async fn create_lesson(
         .prop("video", &*req.video)
         .exec()
         .await?;
+
+    index_queue::sync_or_queue(&services, IndexTarget::Lesson, &lesson.id).await;
    
     services.kafka.publish_cache_invalidation("lesson_created", &lesson.id).await?;
     services.cache.delete_all("lessons:*").await?;
@@ -139,13 +144,15 @@ pub async fn update_lesson(
     if updated_lessons.is_empty() {
         return Err(ApiError::NotFound("Lesson not found".to_string()));
     }
+
+    index_queue::sync_or_queue(&services, IndexTarget::Lesson, &lesson_id).await;
     
     services.kafka.publish_cache_invalidation("lesson_updated", &lesson_id).await?;
     services.cache.delete_all("lessons:*").await?;
     services.cache.delete_all("modules:*").await?;
     services.cache.delete_all("programs:*").await?;
     
-    Ok(HttpResponse::Ok().json(updated_lessons.get(0)))
+    Ok(HttpResponse::Ok().json(updated_lessons.first()))
 }
 
 #[delete("/lessons/{id}")]
@@ -163,6 +170,8 @@ pub async fn delete_lesson(
         .param("lesson_id", lesson_id.clone())
         .fetch::<Lesson>()
         .await?;
+
+    index_queue::sync_or_queue(&services, IndexTarget::Lesson, &lesson_id).await;
     
     services.kafka.publish_cache_invalidation("lesson_deleted", &lesson_id).await?;
     services.cache.delete_all("lessons:*").await?;
@@ -174,12 +183,20 @@ pub async fn delete_lesson(
     })))
 }
 
+/// Signed-in learners get their own progress recorded, as with `/me/lessons/{id}/complete`;
+/// anonymous calls keep setting the lesson's shared `completed` flag.
 #[put("/lessons/{id}/complete")]
 pub async fn mark_lesson_complete(
+    req: HttpRequest,
     path: web::Path<String>,
     services: web::Data<AppServices>,
 ) -> Result<HttpResponse, ApiError> {
     let lesson_id = path.into_inner();
+
+    if let Some(user) = optional_user(&req, &services).await? {
+        let progress = record_lesson_completion(&services, &user, &lesson_id).await?;
+        return Ok(HttpResponse::Ok().json(progress));
+    }
     
     let updated_lessons: Vec<Lesson> = services.neo4j
         .query_nodes(
@@ -199,5 +216,5 @@ pub async fn mark_lesson_complete(
     services.kafka.publish_cache_invalidation("lesson_completed", &lesson_id).await?;
     services.cache.delete(&format!("lessons:{}", lesson_id)).await?;
     
-    Ok(HttpResponse::Ok().json(updated_lessons.get(0)))
+    Ok(HttpResponse::Ok().json(updated_lessons.first()))
 }
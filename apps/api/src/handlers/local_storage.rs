@@ -0,0 +1,119 @@
+use actix_web::{get, http::{header, StatusCode}, put, web, HttpRequest, HttpResponse};
+use std::sync::Arc;
+use crate::dtos::storage::PresignedQuery;
+use crate::handlers::media::{parse_range, ByteRange};
+use crate::service::local_storage::LocalStorage;
+use crate::service::AppServices;
+use crate::traits::object_storage::ObjectStorage;
+use crate::error::ApiError;
+
+fn verified_storage(
+    services: &AppServices,
+    method: &str,
+    bucket: &str,
+    key: &str,
+    query: &PresignedQuery,
+) -> Result<Arc<LocalStorage>, ApiError> {
+    let storage = services
+        .local_storage
+        .clone()
+        .ok_or_else(|| ApiError::NotFound("Local storage is not enabled".to_string()))?;
+
+    if !storage.verify(method, bucket, key, query) {
+        return Err(ApiError::Forbidden("Invalid or expired signature".to_string()));
+    }
+
+    Ok(storage)
+}
+
+#[get("/{bucket}/{key:.*}")]
+pub async fn get_object(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    query: web::Query<PresignedQuery>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let (bucket, key) = path.into_inner();
+    let storage = verified_storage(&services, "GET", &bucket, &key, &query)?;
+
+    let head = storage
+        .head(&bucket, &key)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Object {}/{} not found", bucket, key)))?;
+
+    let range_header = req.headers().get(header::RANGE).and_then(|v| v.to_str().ok());
+
+    let (status, range) = match parse_range(range_header, head.size) {
+        ByteRange::Full => (StatusCode::OK, None),
+        ByteRange::Partial(start, end) => (StatusCode::PARTIAL_CONTENT, Some((start, end))),
+        ByteRange::Unsatisfiable => {
+            return Ok(HttpResponse::build(StatusCode::RANGE_NOT_SATISFIABLE)
+                .insert_header((header::CONTENT_RANGE, format!("bytes */{}", head.size)))
+                .finish());
+        }
+    };
+
+    let object = storage.get(&bucket, &key, range).await?;
+
+    let mut response = HttpResponse::build(status);
+    response
+        .insert_header((header::CONTENT_TYPE, object.content_type.unwrap_or_default()))
+        .insert_header((header::ACCEPT_RANGES, "bytes"))
+        .no_chunking(object.content_length as u64);
+
+    if let Some((start, end)) = range {
+        response.insert_header((header::CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, head.size)));
+    }
+    if let Some(etag) = head.e_tag {
+        response.insert_header((header::ETAG, etag));
+    }
+
+    Ok(response.streaming(object.body))
+}
+
+/// A presigned PUT is only good for the exact body it was issued for.
+fn check_signed_content(req: &HttpRequest, query: &PresignedQuery) -> Result<(), ApiError> {
+    if let Some(expected) = query.content_length {
+        let length = req
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<i64>().ok());
+        if length != Some(expected) {
+            return Err(ApiError::BadRequest(format!("Content-Length must be {}", expected)));
+        }
+    }
+
+    if let Some(expected) = &query.content_type {
+        let content_type = req.headers().get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok());
+        if content_type != Some(expected.as_str()) {
+            return Err(ApiError::BadRequest(format!("Content-Type must be {}", expected)));
+        }
+    }
+
+    Ok(())
+}
+
+#[put("/{bucket}/{key:.*}")]
+pub async fn put_object(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    query: web::Query<PresignedQuery>,
+    payload: web::Payload,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let (bucket, key) = path.into_inner();
+    let storage = verified_storage(&services, "PUT", &bucket, &key, &query)?;
+    check_signed_content(&req, &query)?;
+
+    let target = match (&query.upload_id, query.part_number) {
+        (Some(upload_id), Some(part_number)) => storage.part_path(upload_id, part_number)?,
+        _ => storage.object_path(&bucket, &key)?,
+    };
+
+    let (_, e_tag) = storage.write_stream(&target, payload).await?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header((header::ETAG, e_tag))
+        .finish())
+}
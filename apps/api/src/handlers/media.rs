@@ -0,0 +1,202 @@
+use std::time::Duration;
+use actix_web::{get, http::{header, StatusCode}, web, HttpRequest, HttpResponse};
+use crate::dtos::media::{DeliveryMode, MediaQuery};
+use crate::handlers::auth::current_user;
+use crate::handlers::cohorts::locked_module_ids;
+use crate::models::programe::{Course, Lesson, Module};
+use crate::models::progress::Enrollment;
+use crate::models::upload::split_reference;
+use crate::service::AppServices;
+use crate::error::ApiError;
+
+const PLAYBACK_URL_TTL: Duration = Duration::from_secs(5 * 60);
+const PLAYBACK_CACHE_CONTROL: &str = "private, max-age=3600";
+
+pub enum ByteRange {
+    Full,
+    Partial(i64, i64),
+    Unsatisfiable,
+}
+
+pub fn parse_range(header_value: Option<&str>, size: i64) -> ByteRange {
+    let Some(spec) = header_value.and_then(|h| h.trim().strip_prefix("bytes=")) else {
+        return ByteRange::Full;
+    };
+
+    if spec.contains(',') {
+        return ByteRange::Full;
+    }
+
+    let Some((start, end)) = spec.split_once('-') else {
+        return ByteRange::Full;
+    };
+
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix) => match suffix.parse::<i64>() {
+            Ok(0) => return ByteRange::Unsatisfiable,
+            Ok(n) => ((size - n).max(0), size - 1),
+            Err(_) => return ByteRange::Full,
+        },
+        (start, "") => match start.parse::<i64>() {
+            Ok(s) => (s, size - 1),
+            Err(_) => return ByteRange::Full,
+        },
+        (start, end) => match (start.parse::<i64>(), end.parse::<i64>()) {
+            (Ok(s), Ok(e)) if e >= s => (s, e.min(size - 1)),
+            _ => return ByteRange::Full,
+        },
+    };
+
+    if size == 0 || start >= size {
+        return ByteRange::Unsatisfiable;
+    }
+
+    ByteRange::Partial(start, end)
+}
+
+async fn playable_lesson(
+    req: &HttpRequest,
+    services: &AppServices,
+    lesson_id: &str,
+) -> Result<Lesson, ApiError> {
+    let user = current_user(req, services).await?;
+
+    let lessons: Vec<Lesson> = services.neo4j
+        .query_nodes("MATCH (l:Lesson {id: $lesson_id}) RETURN l")
+        .param("lesson_id", lesson_id.to_string())
+        .fetch_key("l")
+        .fetch()
+        .await?;
+
+    let lesson = lessons
+        .into_iter()
+        .next()
+        .ok_or_else(|| ApiError::NotFound("Lesson not found".to_string()))?;
+
+    let courses: Vec<Course> = services.neo4j
+        .query_nodes(
+            "MATCH (c:Course)-[:HAS_MODULE]->(:Module)-[:HAS_LESSON]->(l:Lesson {id: $lesson_id})
+             RETURN DISTINCT c"
+        )
+        .param("lesson_id", lesson_id.to_string())
+        .fetch_key("c")
+        .fetch()
+        .await?;
+
+    let mut enrolled_course = None;
+    for course in courses {
+        let enrollments: Vec<Enrollment> = services
+            .postgress
+            .query("SELECT * FROM enrollments WHERE user_id = $1::uuid AND course_id = $2")
+            .bind(user.id)
+            .bind(&course.id)
+            .fetch_all()
+            .await?;
+
+        if !enrollments.is_empty() {
+            enrolled_course = Some(course);
+            break;
+        }
+    }
+
+    let course = enrolled_course
+        .ok_or_else(|| ApiError::Forbidden("You are not enrolled in this course".to_string()))?;
+
+    let modules: Vec<Module> = services.neo4j
+        .query_nodes("MATCH (m:Module)-[:HAS_LESSON]->(l:Lesson {id: $lesson_id}) RETURN m")
+        .param("lesson_id", lesson_id.to_string())
+        .fetch_key("m")
+        .fetch()
+        .await?;
+
+    let locked = locked_module_ids(services, user.id, &course.id).await?;
+    if modules.iter().any(|m| locked.contains(&m.id)) {
+        return Err(ApiError::Forbidden("Lesson is not released yet for your cohort".to_string()));
+    }
+
+    Ok(lesson)
+}
+
+#[get("/lessons/{id}/video")]
+pub async fn stream_lesson_video(
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: web::Query<MediaQuery>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let lesson_id = path.into_inner();
+    let lesson = playable_lesson(&req, &services, &lesson_id).await?;
+
+    let (bucket, key) = split_reference(&lesson.video)
+        .ok_or_else(|| ApiError::NotFound("Lesson has no video".to_string()))?;
+
+    if query.mode == DeliveryMode::Redirect {
+        let url = services.storage
+            .presign_get(bucket, key, PLAYBACK_URL_TTL)
+            .await?;
+
+        return Ok(HttpResponse::Found()
+            .insert_header((header::LOCATION, url))
+            .insert_header((header::CACHE_CONTROL, "private, no-store"))
+            .finish());
+    }
+
+    let head = services.storage
+        .head(bucket, key)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Video not found".to_string()))?;
+
+    if let (Some(etag), Some(if_none_match)) = (&head.e_tag, req.headers().get(header::IF_NONE_MATCH))
+        && if_none_match.to_str().map(|v| v == etag).unwrap_or(false)
+    {
+        return Ok(HttpResponse::NotModified()
+            .insert_header((header::ETAG, etag.as_str()))
+            .finish());
+    }
+
+    let range_header = req.headers().get(header::RANGE).and_then(|v| v.to_str().ok());
+
+    let (status, range, content_range) = match parse_range(range_header, head.size) {
+        ByteRange::Full => (StatusCode::OK, None, None),
+        ByteRange::Partial(start, end) => (
+            StatusCode::PARTIAL_CONTENT,
+            Some((start, end)),
+            Some(format!("bytes {}-{}/{}", start, end, head.size)),
+        ),
+        ByteRange::Unsatisfiable => {
+            return Ok(HttpResponse::build(StatusCode::RANGE_NOT_SATISFIABLE)
+                .insert_header((header::CONTENT_RANGE, format!("bytes */{}", head.size)))
+                .insert_header((header::ACCEPT_RANGES, "bytes"))
+                .finish());
+        }
+    };
+
+    let object = services.storage.get(bucket, key, range).await?;
+
+    let content_type = object
+        .content_type
+        .or(head.content_type)
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+
+    let mut response = HttpResponse::build(status);
+    response
+        .insert_header((header::CONTENT_TYPE, content_type))
+        .insert_header((header::ACCEPT_RANGES, "bytes"))
+        .insert_header((header::CACHE_CONTROL, PLAYBACK_CACHE_CONTROL))
+        .no_chunking(object.content_length as u64);
+
+    if let Some(content_range) = content_range {
+        response.insert_header((header::CONTENT_RANGE, content_range));
+    }
+    if let Some(etag) = head.e_tag {
+        response.insert_header((header::ETAG, etag));
+    }
+    if let Some(last_modified) = head.last_modified {
+        response.insert_header((
+            header::LAST_MODIFIED,
+            last_modified.format("%a, %d %b %Y %H:%M:%S GMT").to_string(),
+        ));
+    }
+
+    Ok(response.streaming(object.body))
+}
@@ -3,3 +3,12 @@ pub mod modules;
 pub mod lessons;
 pub mod search;
 pub mod auth;
+pub mod progress;
+pub mod cohorts;
+pub mod notifications;
+pub mod uploads;
+pub mod media;
+pub mod multipart_uploads;
+pub mod local_storage;
+pub mod images;
+pub mod admin;
@@ -2,8 +2,9 @@ use actix_web::web;
 use actix_web::{delete, get, post, put, HttpResponse};
 use crate::dtos::modules::{CreateModuleRequest, UpdateModuleRequest};
 use crate::dtos::programe::ModuleWithLessons;
+use crate::models::index_retry::IndexTarget;
 use crate::models::programe::Module;
-use crate::service::AppServices;
+use crate::service::{content_index, index_queue, AppServices};
 use crate::traits::redis_trait::RedisCache;
 use crate::error::ApiError;
 
@@ -80,8 +81,9 @@ pub async fn create_module(
         .exec()
         .await?;
     
+    let mut attached_lesson_ids = Vec::new();
     for lesson_id in &req.lesson_ids {
-        let _ = services.neo4j.create_relationship(
+        let linked = services.neo4j.create_relationship(
             &module.id,
             lesson_id,
             "Module",
@@ -89,9 +91,17 @@ pub async fn create_module(
             "HAS_LESSON",
             None
         ).await;
+        if linked.is_ok() {
+            attached_lesson_ids.push(lesson_id.clone());
+        }
     }
     
+    index_queue::sync_or_queue(&services, IndexTarget::Module, &module.id).await;
+
     services.kafka.publish_cache_invalidation("module_created", &module.id).await?;
+    for lesson_id in &attached_lesson_ids {
+        services.kafka.publish_cache_invalidation("lesson_attached", lesson_id).await?;
+    }
     services.cache.delete_all("modules:*").await?;
     services.cache.delete_all("programs:*").await?;
     
@@ -137,6 +147,9 @@ pub async fn update_module(
         return Err(ApiError::NotFound("Module not found".to_string()));
     }
     
+    let previous_lesson_ids = content_index::module_lesson_ids(&services, &module_id).await?;
+    let mut attached_lesson_ids = Vec::new();
+
     if let Some(lesson_ids) = &req.lesson_ids {
         services.neo4j
             .query_nodes("MATCH (m:Module {id: $module_id})-[r:HAS_LESSON]->() DELETE r")
@@ -145,7 +158,7 @@ pub async fn update_module(
             .await?;
         
         for lesson_id in lesson_ids {
-            let _ = services.neo4j.create_relationship(
+            let linked = services.neo4j.create_relationship(
                 &module_id,
                 lesson_id,
                 "Module",
@@ -153,15 +166,23 @@ pub async fn update_module(
                 "HAS_LESSON",
                 None
             ).await;
+            if linked.is_ok() && !previous_lesson_ids.contains(lesson_id) {
+                attached_lesson_ids.push(lesson_id.clone());
+            }
         }
     }
-    
+
+    index_queue::sync_or_queue(&services, IndexTarget::Module, &module_id).await;
+    index_queue::sync_all(&services, IndexTarget::Lesson, &previous_lesson_ids).await;
 
     services.kafka.publish_cache_invalidation("module_updated", &module_id).await?;
+    for lesson_id in &attached_lesson_ids {
+        services.kafka.publish_cache_invalidation("lesson_attached", lesson_id).await?;
+    }
     services.cache.delete_all("modules:*").await?;
     services.cache.delete_all("programs:*").await?;
     
-    Ok(HttpResponse::Ok().json(updated_modules.get(0)))
+    Ok(HttpResponse::Ok().json(updated_modules.first()))
 }
 
 #[delete("/modules/{id}")]
@@ -170,6 +191,7 @@ pub async fn delete_module(
     services: web::Data<AppServices>,
 ) -> Result<HttpResponse, ApiError> {
     let module_id = path.into_inner();
+    let lesson_ids = content_index::module_lesson_ids(&services, &module_id).await?;
     
     services.neo4j
         .query_nodes(
@@ -179,6 +201,9 @@ pub async fn delete_module(
         .param("module_id", module_id.clone())
         .fetch::<Module>()
         .await?;
+
+    index_queue::sync_or_queue(&services, IndexTarget::Module, &module_id).await;
+    index_queue::sync_all(&services, IndexTarget::Lesson, &lesson_ids).await;
     
     services.kafka.publish_cache_invalidation("module_deleted", &module_id).await?;
     services.cache.delete_all("modules:*").await?;
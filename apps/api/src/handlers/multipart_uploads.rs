@@ -0,0 +1,269 @@
+use std::time::Duration;
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse};
+use uuid::Uuid;
+use crate::dtos::uploads::{PresignedPartResponse, UploadIntentRequest, UploadSessionResponse, UploadedPartResponse};
+use crate::handlers::auth::current_user;
+use crate::handlers::uploads::{attach_media, validate_upload};
+use crate::models::upload::{MediaKind, UploadSession};
+use crate::service::AppServices;
+use crate::error::ApiError;
+
+pub const MIN_PART_SIZE: i64 = 8 * 1024 * 1024;
+pub const MAX_PART_PAYLOAD_BYTES: usize = 16 * 1024 * 1024;
+const MAX_PARTS: i64 = 10_000;
+const PART_URL_TTL: Duration = Duration::from_secs(60 * 60);
+
+fn part_size_for(size_bytes: i64) -> i64 {
+    let by_count = (size_bytes + MAX_PARTS - 1) / MAX_PARTS;
+    by_count.max(MIN_PART_SIZE)
+}
+
+async fn pending_session(
+    services: &AppServices,
+    session_id: &str,
+    user_id: Uuid,
+) -> Result<UploadSession, ApiError> {
+    let session: UploadSession = services
+        .postgress
+        .query("SELECT * FROM upload_sessions WHERE id = $1::uuid AND user_id = $2::uuid")
+        .bind(session_id)
+        .bind(user_id)
+        .fetch_optional()
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Upload session {} not found", session_id)))?;
+
+    if session.status != "pending" {
+        return Err(ApiError::BadRequest(format!("Upload session is {}", session.status)));
+    }
+
+    Ok(session)
+}
+
+async fn touch_session(services: &AppServices, session: &UploadSession, status: &str) -> Result<UploadSession, ApiError> {
+    services
+        .postgress
+        .query("UPDATE upload_sessions SET status = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1::uuid RETURNING *")
+        .bind(session.id)
+        .bind(status)
+        .fetch_one()
+        .await
+}
+
+fn check_part_number(session: &UploadSession, part_number: i32) -> Result<(), ApiError> {
+    if part_number < 1 || part_number as i64 > session.part_count() {
+        return Err(ApiError::BadRequest(format!(
+            "part_number must be between 1 and {}",
+            session.part_count()
+        )));
+    }
+    Ok(())
+}
+
+#[post("/multipart")]
+pub async fn initiate_multipart_upload(
+    req: HttpRequest,
+    body: web::Json<UploadIntentRequest>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let user = current_user(&req, &services).await?;
+    let body = body.into_inner();
+    let ext = validate_upload(&services, &body, body.kind.max_multipart_size_bytes()).await?;
+
+    let bucket = body.kind.bucket();
+    let key = format!("{}/{}.{}", body.target_id, Uuid::new_v4(), ext);
+
+    let s3_upload_id = services.storage
+        .create_multipart_upload(bucket, &key, &body.content_type)
+        .await?;
+
+    let session: UploadSession = services
+        .postgress
+        .query(
+            r#"
+            INSERT INTO upload_sessions
+                (user_id, kind, target_id, bucket, object_key, s3_upload_id, content_type, size_bytes, part_size)
+            VALUES ($1::uuid, $2, $3, $4, $5, $6, $7, $8::bigint, $9::bigint)
+            RETURNING *
+            "#,
+        )
+        .bind(user.id)
+        .bind(body.kind.as_str())
+        .bind(&body.target_id)
+        .bind(bucket)
+        .bind(&key)
+        .bind(&s3_upload_id)
+        .bind(&body.content_type)
+        .bind(body.size_bytes)
+        .bind(part_size_for(body.size_bytes))
+        .fetch_one()
+        .await?;
+
+    Ok(HttpResponse::Created().json(UploadSessionResponse {
+        part_count: session.part_count(),
+        session,
+        uploaded_parts: vec![],
+    }))
+}
+
+#[get("/multipart/{id}")]
+pub async fn get_multipart_upload(
+    req: HttpRequest,
+    path: web::Path<String>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let session_id = path.into_inner();
+    let user = current_user(&req, &services).await?;
+    let session = pending_session(&services, &session_id, user.id).await?;
+
+    let parts = services.storage
+        .list_parts(&session.bucket, &session.object_key, &session.s3_upload_id)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(UploadSessionResponse {
+        part_count: session.part_count(),
+        session,
+        uploaded_parts: parts
+            .into_iter()
+            .map(|p| UploadedPartResponse {
+                part_number: p.part_number,
+                e_tag: p.e_tag,
+                size: p.size,
+            })
+            .collect(),
+    }))
+}
+
+#[post("/multipart/{id}/parts/{part_number}/presign")]
+pub async fn presign_multipart_part(
+    req: HttpRequest,
+    path: web::Path<(String, i32)>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let (session_id, part_number) = path.into_inner();
+    let user = current_user(&req, &services).await?;
+    let session = pending_session(&services, &session_id, user.id).await?;
+    check_part_number(&session, part_number)?;
+
+    let upload_url = services.storage
+        .presign_upload_part(
+            &session.bucket,
+            &session.object_key,
+            &session.s3_upload_id,
+            part_number,
+            PART_URL_TTL,
+        )
+        .await?;
+
+    touch_session(&services, &session, "pending").await?;
+
+    Ok(HttpResponse::Ok().json(PresignedPartResponse {
+        part_number,
+        upload_url,
+        method: "PUT".to_string(),
+        expires_in_seconds: PART_URL_TTL.as_secs(),
+    }))
+}
+
+#[put("/multipart/{id}/parts/{part_number}")]
+pub async fn upload_multipart_part(
+    req: HttpRequest,
+    path: web::Path<(String, i32)>,
+    body: web::Bytes,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let (session_id, part_number) = path.into_inner();
+    let user = current_user(&req, &services).await?;
+    let session = pending_session(&services, &session_id, user.id).await?;
+    check_part_number(&session, part_number)?;
+
+    let is_last = part_number as i64 == session.part_count();
+    if body.is_empty() || (!is_last && (body.len() as i64) < session.part_size) {
+        return Err(ApiError::BadRequest(format!(
+            "Part {} must be {} bytes",
+            part_number, session.part_size
+        )));
+    }
+
+    let e_tag = services.storage
+        .upload_part(
+            &session.bucket,
+            &session.object_key,
+            &session.s3_upload_id,
+            part_number,
+            body.to_vec(),
+        )
+        .await?;
+
+    touch_session(&services, &session, "pending").await?;
+
+    Ok(HttpResponse::Ok().json(UploadedPartResponse {
+        part_number,
+        e_tag,
+        size: body.len() as i64,
+    }))
+}
+
+#[post("/multipart/{id}/complete")]
+pub async fn complete_multipart(
+    req: HttpRequest,
+    path: web::Path<String>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let session_id = path.into_inner();
+    let user = current_user(&req, &services).await?;
+    let session = pending_session(&services, &session_id, user.id).await?;
+
+    let mut parts = services.storage
+        .list_parts(&session.bucket, &session.object_key, &session.s3_upload_id)
+        .await?;
+    parts.sort_by_key(|p| p.part_number);
+
+    let uploaded: i64 = parts.iter().map(|p| p.size).sum();
+    if parts.len() as i64 != session.part_count() || uploaded != session.size_bytes {
+        return Err(ApiError::BadRequest(format!(
+            "Upload incomplete: {} of {} parts, {} of {} bytes",
+            parts.len(),
+            session.part_count(),
+            uploaded,
+            session.size_bytes
+        )));
+    }
+
+    services.storage
+        .complete_multipart_upload(&session.bucket, &session.object_key, &session.s3_upload_id, &parts)
+        .await?;
+
+    let session = touch_session(&services, &session, "completed").await?;
+
+    let kind = MediaKind::parse(&session.kind)
+        .ok_or_else(|| ApiError::Internal(format!("Unknown media kind {}", session.kind)))?;
+    let reference = session.reference();
+    let attached = attach_media(&services, kind, &session.target_id, &reference).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "upload_id": session.id,
+        "reference": reference,
+        "attached_to": attached
+    })))
+}
+
+#[delete("/multipart/{id}")]
+pub async fn abort_multipart(
+    req: HttpRequest,
+    path: web::Path<String>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let session_id = path.into_inner();
+    let user = current_user(&req, &services).await?;
+    let session = pending_session(&services, &session_id, user.id).await?;
+
+    services.storage
+        .abort_multipart_upload(&session.bucket, &session.object_key, &session.s3_upload_id)
+        .await?;
+
+    touch_session(&services, &session, "aborted").await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "message": "Upload aborted"
+    })))
+}
@@ -0,0 +1,101 @@
+use actix_web::{get, put, web, HttpRequest, HttpResponse};
+use uuid::Uuid;
+use crate::dtos::notifications::NotificationListQuery;
+use crate::handlers::auth::current_user;
+use crate::models::notification::{Notification, UnreadCount};
+use crate::service::AppServices;
+use crate::error::ApiError;
+
+#[get("/notifications")]
+pub async fn list_notifications(
+    req: HttpRequest,
+    query: web::Query<NotificationListQuery>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let user = current_user(&req, &services).await?;
+
+    let notifications: Vec<Notification> = services
+        .postgress
+        .query(
+            r#"
+            SELECT * FROM notifications
+            WHERE user_id = $1::uuid AND (NOT $2::boolean OR read_at IS NULL)
+            ORDER BY created_at DESC
+            OFFSET $3::bigint LIMIT $4::bigint
+            "#,
+        )
+        .bind(user.id)
+        .bind(query.unread_only)
+        .bind(query.from.max(0))
+        .bind(query.size.clamp(1, 100))
+        .fetch_all()
+        .await?;
+
+    let unread: UnreadCount = services
+        .postgress
+        .query("SELECT COUNT(*) AS unread FROM notifications WHERE user_id = $1::uuid AND read_at IS NULL")
+        .bind(user.id)
+        .fetch_one()
+        .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "notifications": notifications,
+        "unread_count": unread.unread,
+        "from": query.from,
+        "size": query.size
+    })))
+}
+
+#[put("/notifications/{id}/read")]
+pub async fn mark_notification_read(
+    req: HttpRequest,
+    path: web::Path<String>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let notification_id = path.into_inner();
+    let notification_id = Uuid::parse_str(&notification_id)
+        .map_err(|_| ApiError::BadRequest(format!("Invalid notification id {}", notification_id)))?;
+    let user = current_user(&req, &services).await?;
+
+    let notification: Notification = services
+        .postgress
+        .query(
+            r#"
+            UPDATE notifications SET read_at = COALESCE(read_at, CURRENT_TIMESTAMP)
+            WHERE id = $1::uuid AND user_id = $2::uuid
+            RETURNING *
+            "#,
+        )
+        .bind(notification_id)
+        .bind(user.id)
+        .fetch_optional()
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Notification not found".to_string()))?;
+
+    Ok(HttpResponse::Ok().json(notification))
+}
+
+#[put("/notifications/read-all")]
+pub async fn mark_all_notifications_read(
+    req: HttpRequest,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let user = current_user(&req, &services).await?;
+
+    let updated: Vec<Notification> = services
+        .postgress
+        .query(
+            r#"
+            UPDATE notifications SET read_at = CURRENT_TIMESTAMP
+            WHERE user_id = $1::uuid AND read_at IS NULL
+            RETURNING *
+            "#,
+        )
+        .bind(user.id)
+        .fetch_all()
+        .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "updated": updated.len()
+    })))
+}
@@ -1,9 +1,13 @@
 use actix_web::web;
 use actix_web::{delete, get, post, put, HttpResponse};
-use crate::dtos::programe::{ModuleWithLessons, ProgramDetail, UpdateCourseRequest};
+use crate::dtos::programe::{ModuleWithLessons, ProgramDetail, RelatedQuery, UpdateCourseRequest};
+use crate::handlers::images::schedule_cover_variants;
+use crate::models::index_retry::IndexTarget;
 use crate::models::programe::{Course, Lesson, Module};
-use crate::service::AppServices;
+use crate::models::search::RelatedCourse;
+use crate::service::{content_index, index_queue, related_courses, AppServices};
 use crate::traits::redis_trait::RedisCache;
+use crate::workers::documents::schedule_course_documents;
 use crate::{dtos::programe::CreateCourseRequest, error::ApiError};
 
 #[get("/programs")]
@@ -98,6 +102,36 @@ pub async fn get_program_by_id(
     })))
 }
 
+#[get("/programs/{id}/related")]
+pub async fn get_related_programs(
+    path: web::Path<String>,
+    query: web::Query<RelatedQuery>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let course_id = path.into_inner();
+    if !(1..=20).contains(&query.limit) {
+        return Err(ApiError::BadRequest("limit must be between 1 and 20".to_string()));
+    }
+
+    let key = format!("programs:{}:related:{}", course_id, query.limit);
+
+    if let Some(cached_related) = services.cache.get::<Vec<RelatedCourse>>(&key).await? {
+        return Ok(HttpResponse::Ok().json(serde_json::json!({
+            "related": cached_related,
+            "source": "cache"
+        })));
+    }
+
+    let related = related_courses::related_courses(&services, &course_id, query.limit).await?;
+
+    services.cache.set(&key, &related, 300).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "related": related,
+        "source": "opensearch+neo4j"
+    })))
+}
+
 #[post("/programs")]
 pub async fn create_program(
     req: web::Json<CreateCourseRequest>,
@@ -116,11 +150,16 @@ pub async fn create_program(
     .await?;
    
     for m in &*req.module_ids{
-        let _ = services.neo4j.create_relationship(&course.id, m, "Course", "Module", "HAS_MODULE", None);
+        let _ = services.neo4j.create_relationship(&course.id, m, "Course", "Module", "HAS_MODULE", None).await;
     }
 
+    index_queue::sync_all(&services, IndexTarget::Module, &content_index::course_module_ids(&services, &course.id).await?).await;
+
+    schedule_cover_variants(&services, &course.cover);
+    schedule_course_documents(&services, &course.id);
+
     services.kafka.publish_cache_invalidation("course_created", &course.id).await?;
-    services.opensearch.index_course(&course).await?; 
+    index_queue::index_course(&services, &course).await;
     services.cache.delete_all("programs:*").await?;
     
     Ok(HttpResponse::Created().json(course))
@@ -187,6 +226,8 @@ pub async fn update_program(
         return Err(ApiError::NotFound("Program not found".to_string()));
     }
     
+    let previous_module_ids = content_index::course_module_ids(&services, &course_id).await?;
+
     if let Some(module_ids) = &req.module_ids {
         services.neo4j
             .query_nodes("MATCH (c:Course {id: $course_id})-[r:HAS_MODULE]->() DELETE r")
@@ -206,13 +247,27 @@ pub async fn update_program(
         }
     }
 
-    if let Some(updated_course) = updated_courses.get(0) {
-        services.opensearch.index_course(updated_course).await?;
+    let module_ids = content_index::course_module_ids(&services, &course_id).await?;
+    let detached_module_ids: Vec<String> = previous_module_ids
+        .into_iter()
+        .filter(|id| !module_ids.contains(id))
+        .collect();
+    index_queue::sync_all(&services, IndexTarget::Module, &module_ids).await;
+    index_queue::sync_all(&services, IndexTarget::Module, &detached_module_ids).await;
+
+    if let Some(updated_course) = updated_courses.first() {
+        if req.cover.is_some() {
+            schedule_cover_variants(&services, &updated_course.cover);
+        }
+        if req.documents.is_some() {
+            schedule_course_documents(&services, &updated_course.id);
+        }
+        index_queue::index_course(&services, updated_course).await;
     }
     services.kafka.publish_cache_invalidation("course_updated", &course_id).await?;
     services.cache.delete_all("programs:*").await?;
     
-    Ok(HttpResponse::Ok().json(updated_courses.get(0)))
+    Ok(HttpResponse::Ok().json(updated_courses.first()))
 }
 
 #[delete("/programs/{id}")]
@@ -221,6 +276,7 @@ pub async fn delete_program(
     services: web::Data<AppServices>,
 ) -> Result<HttpResponse, ApiError> {
     let course_id = path.into_inner();
+    let module_ids = content_index::course_module_ids(&services, &course_id).await?;
 
     services.neo4j
         .query_nodes("MATCH (c:Course {id: $course_id}) DETACH DELETE c")
@@ -228,7 +284,8 @@ pub async fn delete_program(
         .fetch::<Course>()
         .await?;
 
-    services.opensearch.delete_course(&course_id).await?;
+    index_queue::remove_course(&services, &course_id).await;
+    index_queue::sync_all(&services, IndexTarget::Module, &module_ids).await;
     
     services.kafka.publish_cache_invalidation("course_deleted", &course_id).await?;
     
@@ -0,0 +1,269 @@
+use std::collections::HashSet;
+use actix_web::{get, post, put, web, HttpRequest, HttpResponse};
+use crate::dtos::programe::ModuleWithLessons;
+use crate::dtos::progress::{ContinueLearningItem, NextLesson};
+use crate::handlers::auth::current_user;
+use crate::handlers::cohorts::locked_module_ids;
+use crate::models::auth::User;
+use crate::models::programe::{Course, Module};
+use crate::models::progress::{Enrollment, LessonProgress};
+use crate::service::AppServices;
+use crate::traits::redis_trait::RedisCache;
+use crate::error::ApiError;
+
+pub fn continue_cache_key(user_id: &str) -> String {
+    format!("progress:{}:continue", user_id)
+}
+
+pub async fn course_modules(
+    services: &AppServices,
+    course_id: &str,
+) -> Result<Vec<ModuleWithLessons>, ApiError> {
+    let mut modules: Vec<ModuleWithLessons> = services.neo4j
+        .query_nodes(
+            "MATCH (c:Course {id: $course_id})-[:HAS_MODULE]->(m:Module)
+             OPTIONAL MATCH (m)-[:HAS_LESSON]->(l:Lesson)
+             RETURN m, collect(DISTINCT l) as lessons"
+        )
+        .param("course_id", course_id.to_string())
+        .fetch_all::<ModuleWithLessons>()
+        .await?;
+
+    modules.sort_by_key(|m| m.module.order);
+    for m in modules.iter_mut() {
+        m.lessons.sort_by_key(|l| l.order);
+    }
+
+    Ok(modules)
+}
+
+pub fn next_lesson_for(
+    course: &Course,
+    modules: &[ModuleWithLessons],
+    completed_ids: &HashSet<String>,
+    locked_modules: &HashSet<String>,
+) -> ContinueLearningItem {
+    let completed_titles: HashSet<&str> = modules
+        .iter()
+        .flat_map(|m| m.lessons.iter())
+        .filter(|l| completed_ids.contains(&l.id))
+        .map(|l| l.title.as_str())
+        .collect();
+
+    let mut next_lesson = None;
+    let mut completed_lessons = 0;
+    let mut total_lessons = 0;
+    let mut remaining_minutes = 0;
+
+    for m in modules {
+        for lesson in &m.lessons {
+            total_lessons += 1;
+
+            if completed_ids.contains(&lesson.id) {
+                completed_lessons += 1;
+                continue;
+            }
+
+            remaining_minutes += lesson.duration_minutes;
+
+            let unlocked = lesson.prerequisites.iter().all(|p| {
+                completed_ids.contains(p) || completed_titles.contains(p.as_str())
+            });
+
+            if next_lesson.is_none() && unlocked && !locked_modules.contains(&m.module.id) {
+                next_lesson = Some(NextLesson {
+                    module_id: m.module.id.clone(),
+                    module_title: m.module.title.clone(),
+                    lesson: lesson.clone(),
+                });
+            }
+        }
+    }
+
+    ContinueLearningItem {
+        course_id: course.id.clone(),
+        course_title: course.title.clone(),
+        blocked: next_lesson.is_none() && completed_lessons < total_lessons,
+        next_lesson,
+        completed_lessons,
+        total_lessons,
+        remaining_minutes,
+    }
+}
+
+#[post("/enrollments/{course_id}")]
+pub async fn enroll(
+    req: HttpRequest,
+    path: web::Path<String>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let course_id = path.into_inner();
+    let user = current_user(&req, &services).await?;
+
+    let courses: Vec<Course> = services.neo4j
+        .query_nodes("MATCH (c:Course {id: $course_id}) RETURN c")
+        .param("course_id", course_id.clone())
+        .fetch_key("c")
+        .fetch()
+        .await?;
+
+    if courses.is_empty() {
+        return Err(ApiError::NotFound(format!("Course {} not found", course_id)));
+    }
+
+    let enrollment: Enrollment = services
+        .postgress
+        .query(
+            r#"
+            INSERT INTO enrollments (user_id, course_id)
+            VALUES ($1::uuid, $2)
+            ON CONFLICT (user_id, course_id) DO UPDATE SET enrolled_at = enrollments.enrolled_at
+            RETURNING *
+            "#,
+        )
+        .bind(user.id)
+        .bind(&course_id)
+        .fetch_one()
+        .await?;
+
+    services.cache.delete_all(&format!("progress:{}:*", user.id)).await?;
+
+    Ok(HttpResponse::Created().json(enrollment))
+}
+
+/// Records that `user` completed a lesson of a course they are enrolled in and whose
+/// module is released to them.
+pub async fn record_lesson_completion(
+    services: &AppServices,
+    user: &User,
+    lesson_id: &str,
+) -> Result<LessonProgress, ApiError> {
+    let courses: Vec<Course> = services.neo4j
+        .query_nodes(
+            "MATCH (c:Course)-[:HAS_MODULE]->(:Module)-[:HAS_LESSON]->(l:Lesson {id: $lesson_id})
+             RETURN DISTINCT c"
+        )
+        .param("lesson_id", lesson_id.to_string())
+        .fetch_key("c")
+        .fetch()
+        .await?;
+
+    let course = courses
+        .into_iter()
+        .next()
+        .ok_or_else(|| ApiError::NotFound("Lesson not found".to_string()))?;
+
+    let enrollments: Vec<Enrollment> = services
+        .postgress
+        .query("SELECT * FROM enrollments WHERE user_id = $1::uuid AND course_id = $2")
+        .bind(user.id)
+        .bind(&course.id)
+        .fetch_all()
+        .await?;
+
+    if enrollments.is_empty() {
+        return Err(ApiError::Forbidden(format!("Not enrolled in course {}", course.id)));
+    }
+
+    let modules: Vec<Module> = services.neo4j
+        .query_nodes("MATCH (m:Module)-[:HAS_LESSON]->(l:Lesson {id: $lesson_id}) RETURN m")
+        .param("lesson_id", lesson_id.to_string())
+        .fetch_key("m")
+        .fetch()
+        .await?;
+
+    let locked = locked_module_ids(services, user.id, &course.id).await?;
+    if modules.iter().any(|m| locked.contains(&m.id)) {
+        return Err(ApiError::BadRequest("Lesson is not released yet for your cohort".to_string()));
+    }
+
+    let progress: LessonProgress = services
+        .postgress
+        .query(
+            r#"
+            INSERT INTO lesson_progress (user_id, course_id, lesson_id)
+            VALUES ($1::uuid, $2, $3)
+            ON CONFLICT (user_id, lesson_id) DO UPDATE SET completed_at = lesson_progress.completed_at
+            RETURNING *
+            "#,
+        )
+        .bind(user.id)
+        .bind(&course.id)
+        .bind(lesson_id)
+        .fetch_one()
+        .await?;
+
+    services.kafka.publish_lesson_completed(&user.id.to_string(), &course.id, lesson_id).await?;
+    services.cache.delete_all(&format!("progress:{}:*", user.id)).await?;
+
+    Ok(progress)
+}
+
+#[put("/lessons/{id}/complete")]
+pub async fn complete_lesson(
+    req: HttpRequest,
+    path: web::Path<String>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let user = current_user(&req, &services).await?;
+    let progress = record_lesson_completion(&services, &user, &path.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(progress))
+}
+
+#[get("/continue")]
+pub async fn continue_learning(
+    req: HttpRequest,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let user = current_user(&req, &services).await?;
+    let key = continue_cache_key(&user.id.to_string());
+
+    if let Some(cached) = services.cache.get::<Vec<ContinueLearningItem>>(&key).await? {
+        return Ok(HttpResponse::Ok().json(serde_json::json!({
+            "courses": cached,
+            "source": "cache"
+        })));
+    }
+
+    let enrollments: Vec<Enrollment> = services
+        .postgress
+        .query("SELECT * FROM enrollments WHERE user_id = $1::uuid ORDER BY enrolled_at DESC")
+        .bind(user.id)
+        .fetch_all()
+        .await?;
+
+    let progress: Vec<LessonProgress> = services
+        .postgress
+        .query("SELECT * FROM lesson_progress WHERE user_id = $1::uuid")
+        .bind(user.id)
+        .fetch_all()
+        .await?;
+
+    let completed_ids: HashSet<String> = progress.into_iter().map(|p| p.lesson_id).collect();
+
+    let mut items = Vec::new();
+    for enrollment in enrollments {
+        let courses: Vec<Course> = services.neo4j
+            .query_nodes("MATCH (c:Course {id: $course_id}) RETURN c")
+            .param("course_id", enrollment.course_id.clone())
+            .fetch_key("c")
+            .fetch()
+            .await?;
+
+        let Some(course) = courses.into_iter().next() else {
+            continue;
+        };
+
+        let modules = course_modules(&services, &course.id).await?;
+        let locked = locked_module_ids(&services, user.id, &course.id).await?;
+        items.push(next_lesson_for(&course, &modules, &completed_ids, &locked));
+    }
+
+    services.cache.set(&key, &items, 300).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "courses": items,
+        "source": "neo4j"
+    })))
+}
@@ -1,82 +1,301 @@
 use actix_web::web;
-use actix_web::{post, HttpResponse};
-use crate::dtos::search::{CourseSearchResult, FilterRequest, SearchRequest, SearchResultResponse};
-use crate::models::programe::Course;
-use crate::models::search::FilterCondition;
-use crate::service::AppServices;
+use actix_web::{get, post, HttpRequest, HttpResponse};
+use uuid::Uuid;
+use crate::dtos::search::{CourseSearchResult, FilterRequest, SearchClickRequest, SearchRequest, SearchResultResponse, SuggestQuery};
+use crate::handlers::auth::optional_user;
+use crate::models::search::{FilterCondition, PageRequest, Personalization, SearchCursor, Suggestions};
+use crate::models::search_query::{parse_query, ParsedQuery};
+use crate::service::{content_index::group_content_hits, opensearch::PROGRAMS_ALIAS, learner_profile, search_analytics::{self, SearchLog}, search_fallback, AppServices};
 use crate::error::ApiError;
 use crate::traits::redis_trait::RedisCache;
 
-#[post("/programs/search")]
-pub async fn search_programs(
-    req: web::Json<SearchRequest>,
-    services: web::Data<AppServices>,
-) -> Result<HttpResponse, ApiError> {
-    
-    let cache_key = format!(
-        "search:programs:{}:{}:{}",
-        req.query,
-        req.from,
-        req.size
-    );
-    
-    if let Some(cached_results) = services.cache.get::<SearchResultResponse<CourseSearchResult>>(&cache_key).await? {
-        return Ok(HttpResponse::Ok().json(cached_results));
+const DOCUMENT_MATCH_LIMIT: i64 = 100;
+const CONTENT_MATCH_LIMIT: i64 = 200;
+const MAX_SUGGESTIONS_PER_TYPE: i64 = 20;
+const POPULAR_PREFIX_WINDOW_SECONDS: u64 = 60 * 60;
+const POPULAR_PREFIX_THRESHOLD: i64 = 3;
+const SUGGEST_CACHE_TTL_SECONDS: u64 = 10 * 60;
+const MAX_RESULT_WINDOW: i64 = 10_000;
+const MAX_PAGE_SIZE: i64 = 100;
+const PERSONALIZATION_CACHE_TTL_SECONDS: u64 = 2 * 60;
+/// Logged apart from plain searches so click-through can compare the two rankings.
+const PERSONALIZED_ENDPOINT: &str = "search:personalized";
+
+fn page_request(from: i64, size: i64, cursor: &Option<String>, point_in_time: bool) -> Result<PageRequest, ApiError> {
+    let cursor = cursor
+        .as_deref()
+        .map(SearchCursor::decode)
+        .transpose()
+        .map_err(ApiError::BadRequest)?;
+
+    if from < 0 || !(1..=MAX_PAGE_SIZE).contains(&size) {
+        return Err(ApiError::BadRequest(format!(
+            "from must be non-negative and size between 1 and {}",
+            MAX_PAGE_SIZE
+        )));
     }
-    
+
+    if cursor.is_none() && from + size > MAX_RESULT_WINDOW {
+        return Err(ApiError::BadRequest(format!(
+            "from + size may not exceed {}; page with next_cursor instead",
+            MAX_RESULT_WINDOW
+        )));
+    }
+
+    Ok(PageRequest { from, size, cursor, point_in_time })
+}
+
+/// Point-in-time pages belong to one client's snapshot, so they never go through the cache.
+fn cacheable(page: &PageRequest) -> bool {
+    !page.point_in_time && page.cursor.as_ref().map(|c| c.pit_id.is_none()).unwrap_or(true)
+}
+
+fn page_key(from: i64, cursor: &Option<String>) -> String {
+    cursor.clone().unwrap_or_else(|| from.to_string())
+}
+
+async fn opensearch_results(
+    services: &AppServices,
+    req: &SearchRequest,
+    parsed: &ParsedQuery,
+    personalization: Option<&Personalization>,
+    page: &PageRequest,
+) -> Result<SearchResultResponse<CourseSearchResult>, ApiError> {
+    let free_text = parsed.free_text();
     let fields: Vec<&str> = req.fields.iter().map(|s| s.as_str()).collect();
+
+    let mut document_matches = services.opensearch
+        .search_documents(&free_text, Some(&req.highlight), DOCUMENT_MATCH_LIMIT)
+        .await?;
+    let mut content_matches = group_content_hits(
+        services.opensearch
+            .search_content(&free_text, CONTENT_MATCH_LIMIT)
+            .await?,
+    );
+
+    let mut matched_course_ids: Vec<String> = document_matches.keys().cloned().collect();
+    matched_course_ids.extend(
+        content_matches
+            .keys()
+            .filter(|id| !document_matches.contains_key(*id))
+            .cloned(),
+    );
     
     let search_response = services.opensearch
         .search::<CourseSearchResult>(
-            "programs",
-            &req.query,
+            PROGRAMS_ALIAS,
+            parsed,
             fields,
-            req.from,
-            req.size,
+            &matched_course_ids,
+            Some(&req.highlight),
+            personalization,
+            page,
         )
         .await?;
+
+    let results = search_response
+        .results
+        .into_iter()
+        .map(|mut course| {
+            course.matched_in = document_matches.remove(&course.id);
+            if let Some(document) = &course.matched_in
+                && !document.fragments.is_empty()
+            {
+                course.highlights.insert("documents".to_string(), document.fragments.clone());
+            }
+            course.matched_modules = content_matches.remove(&course.id).unwrap_or_default();
+            course
+        })
+        .collect();
     
-    let response = SearchResultResponse {
+    Ok(SearchResultResponse {
         total: search_response.total,
-        results: search_response.results,
+        results,
         from: req.from,
         size: req.size,
         source: "opensearch".to_string(),
+        facets: None,
+        next_cursor: search_response.next_cursor.map(|c| c.encode()),
+        search_id: None,
+        personalized: personalization.is_some(),
+    })
+}
+
+async fn neo4j_fallback_results(
+    services: &AppServices,
+    req: &SearchRequest,
+    parsed: &ParsedQuery,
+) -> Result<SearchResultResponse<CourseSearchResult>, ApiError> {
+    let (total, courses) = search_fallback::search_courses(services, parsed, req.from, req.size).await?;
+
+    Ok(SearchResultResponse {
+        total,
+        results: courses.into_iter().map(CourseSearchResult::from).collect(),
+        from: req.from,
+        size: req.size,
+        source: "neo4j-fallback".to_string(),
+        facets: None,
+        next_cursor: None,
+        search_id: None,
+        personalized: false,
+    })
+}
+
+/// The signed-in learner, when they asked for personal ranking. Failing to identify
+/// them only costs the personal ranking, never the search.
+async fn personalizing_user(http_req: &HttpRequest, services: &AppServices, req: &SearchRequest) -> Option<Uuid> {
+    if !req.personalize {
+        return None;
+    }
+
+    match optional_user(http_req, services).await {
+        Ok(user) => user.map(|u| u.id),
+        Err(e) => {
+            eprintln!("⚠ Could not identify the learner for search personalization: {}", e);
+            None
+        }
+    }
+}
+
+/// Ranking signals for a learner. Cached briefly under their progress prefix, so
+/// completing a lesson or joining a course drops them.
+async fn personalization_for(services: &AppServices, user_id: Uuid) -> Option<Personalization> {
+    let cache_key = format!("progress:{}:personalization", user_id);
+
+    let personalization = match services.cache.get::<Personalization>(&cache_key).await.ok().flatten() {
+        Some(personalization) => personalization,
+        None => match learner_profile::personalization(services, user_id).await {
+            Ok(personalization) => {
+                if let Err(e) = services.cache.set(&cache_key, &personalization, PERSONALIZATION_CACHE_TTL_SECONDS).await {
+                    eprintln!("⚠ Could not cache search personalization: {}", e);
+                }
+                personalization
+            }
+            Err(e) => {
+                eprintln!("⚠ Could not load search personalization: {}", e);
+                return None;
+            }
+        },
     };
-    
-    services.cache.set(&cache_key, &response, 300).await?;
-    
-    Ok(HttpResponse::Ok().json(response))
+
+    (!personalization.is_empty()).then_some(personalization)
 }
 
-#[post("/programs/filter")]
-pub async fn filter_programs(
-    req: web::Json<FilterRequest>,
+#[post("/programs/search")]
+pub async fn search_programs(
+    http_req: HttpRequest,
+    req: web::Json<SearchRequest>,
     services: web::Data<AppServices>,
 ) -> Result<HttpResponse, ApiError> {
-    
+    req.highlight.validate().map_err(ApiError::BadRequest)?;
+    let page = page_request(req.from, req.size, &req.cursor, req.point_in_time)?;
+
+    let parsed = match &req.q {
+        Some(q) => parse_query(q).map_err(|e| ApiError::BadRequest(format!("Invalid query: {}", e)))?,
+        None => ParsedQuery::text(&req.query),
+    };
+    let user_id = personalizing_user(&http_req, &services, &req).await;
+    let mut log = SearchLog::start("search", req.q.as_deref().unwrap_or(&req.query), &parsed.filters);
+
     let cache_key = format!(
-        "filter:programs:{}:{}:{}:{}:{}",
-        req.status.as_ref().unwrap_or(&"none".to_string()),
-        req.min_duration.unwrap_or(0),
-        req.max_duration.unwrap_or(0),
-        req.from,
-        req.size
+        "search:programs:{}:{}:{}:{}:{}:{}:{}:{}:{}",
+        user_id.map(|id| id.to_string()).unwrap_or_else(|| "all".to_string()),
+        req.query,
+        req.q.as_deref().unwrap_or(""),
+        page_key(req.from, &req.cursor),
+        req.size,
+        req.highlight.fragment_size,
+        req.highlight.number_of_fragments,
+        req.highlight.pre_tag,
+        req.highlight.post_tag
     );
     
-    if let Some(cached_results) = services.cache.get::<SearchResultResponse<CourseSearchResult>>(&cache_key).await? {
+    if cacheable(&page)
+        && let Some(mut cached_results) = services.cache.get::<SearchResultResponse<CourseSearchResult>>(&cache_key).await?
+    {
+        if cached_results.personalized {
+            log.set_endpoint(PERSONALIZED_ENDPOINT);
+        }
+        cached_results.search_id = Some(log.record(&services, cached_results.total));
         return Ok(HttpResponse::Ok().json(cached_results));
     }
+
+    let personalization = match user_id {
+        Some(user_id) => personalization_for(&services, user_id).await,
+        None => None,
+    };
+    if personalization.is_some() {
+        log.set_endpoint(PERSONALIZED_ENDPOINT);
+    }
+
+    // A cursor points into OpenSearch's ordering, which the fallback cannot continue.
+    let mut response = match opensearch_results(&services, &req, &parsed, personalization.as_ref(), &page).await {
+        Ok(response) => {
+            if cacheable(&page) {
+                services.cache.set(&cache_key, &response, 300).await?;
+            }
+            response
+        }
+        Err(e) if page.cursor.is_none() => {
+            eprintln!("⚠ OpenSearch search failed, falling back to Neo4j: {}", e);
+            neo4j_fallback_results(&services, &req, &parsed).await?
+        }
+        Err(e) => return Err(e),
+    };
+    response.search_id = Some(log.record(&services, response.total));
     
+    Ok(HttpResponse::Ok().json(response))
+}
+
+fn optional<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(|v| v.to_string()).unwrap_or_else(|| "none".to_string())
+}
+
+/// Every field of the request takes part, so two different filters never share an entry.
+fn filter_cache_key(prefix: &str, req: &FilterRequest) -> String {
+    format!(
+        "{}:{}:{}:{}:{}:{}:{}:{}:{}:{}:{}:{}:{}:{}:{}:{}:{}",
+        prefix,
+        optional(&req.status),
+        optional(&req.min_duration),
+        optional(&req.max_duration),
+        req.prerequisites.as_ref().map(|p| p.join(",")).unwrap_or_else(|| "none".to_string()),
+        optional(&req.title_match),
+        optional(&req.category),
+        optional(&req.level),
+        optional(&req.instructor),
+        optional(&req.min_rating),
+        optional(&req.max_rating),
+        optional(&req.featured),
+        req.filter
+            .as_ref()
+            .and_then(|f| serde_json::to_string(f).ok())
+            .unwrap_or_else(|| "none".to_string()),
+        page_key(req.from, &req.cursor),
+        req.size,
+        optional(&req.sort_by),
+        optional(&req.sort_order)
+    )
+}
+
+fn filter_conditions(req: &FilterRequest) -> Result<Vec<FilterCondition>, ApiError> {
     let mut filters: Vec<FilterCondition> = Vec::new();
-    
-    if let Some(status) = &req.status {
-        filters.push(FilterCondition::Term {
-            field: "status".to_string(),
-            value: status.clone(),
-        });
+
+    let terms = [
+        ("status", &req.status),
+        ("category", &req.category),
+        ("level", &req.level),
+        ("instructor", &req.instructor),
+    ];
+    for (field, value) in terms {
+        if let Some(value) = value {
+            filters.push(FilterCondition::Term {
+                field: field.to_string(),
+                value: value.clone(),
+            });
+        }
     }
-    
+
     if req.min_duration.is_some() || req.max_duration.is_some() {
         filters.push(FilterCondition::Range {
             field: "total_duration_minutes".to_string(),
@@ -86,43 +305,91 @@ pub async fn filter_programs(
             lt: None,
         });
     }
-    
-    if let Some(prereqs) = &req.prerequisites {
-        if !prereqs.is_empty() {
-            filters.push(FilterCondition::Terms {
-                field: "prerequisites".to_string(),
-                values: prereqs.clone(),
-            });
-        }
+
+    if req.min_rating.is_some() || req.max_rating.is_some() {
+        filters.push(FilterCondition::FloatRange {
+            field: "rating".to_string(),
+            gte: req.min_rating,
+            lte: req.max_rating,
+            gt: None,
+            lt: None,
+        });
     }
-    
+
+    if let Some(featured) = req.featured {
+        filters.push(FilterCondition::Term {
+            field: "featured".to_string(),
+            value: featured.to_string(),
+        });
+    }
+
+    if let Some(prereqs) = &req.prerequisites
+        && !prereqs.is_empty()
+    {
+        filters.push(FilterCondition::Terms {
+            field: "prerequisites".to_string(),
+            values: prereqs.clone(),
+        });
+    }
+
     if let Some(title) = &req.title_match {
         filters.push(FilterCondition::Match {
             field: "title".to_string(),
             value: title.clone(),
         });
     }
+
+    if let Some(filter) = &req.filter {
+        filter.validate().map_err(ApiError::BadRequest)?;
+        filters.push(filter.clone());
+    }
+
+    Ok(filters)
+}
+
+#[post("/programs/filter")]
+pub async fn filter_programs(
+    req: web::Json<FilterRequest>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let page = page_request(req.from, req.size, &req.cursor, req.point_in_time)?;
+    let filters = filter_conditions(&req)?;
+    let log = SearchLog::start("filter", req.title_match.as_deref().unwrap_or(""), &filters);
+    let cache_key = filter_cache_key("filter:programs", &req);
+    
+    if cacheable(&page)
+        && let Some(mut cached_results) = services.cache.get::<SearchResultResponse<CourseSearchResult>>(&cache_key).await?
+    {
+        cached_results.search_id = Some(log.record(&services, cached_results.total));
+        return Ok(HttpResponse::Ok().json(cached_results));
+    }
     
     let filter_response = services.opensearch
         .filter::<CourseSearchResult>(
-            "programs",
+            PROGRAMS_ALIAS,
             filters,
-            req.from,
-            req.size,
+            &page,
             req.sort_by.as_deref(),
             req.sort_order.as_deref(),
         )
         .await?;
     
-    let response = SearchResultResponse {
+    let mut response = SearchResultResponse {
         total: filter_response.total,
         results: filter_response.results,
         from: req.from,
         size: req.size,
         source: "opensearch".to_string(),
+        facets: filter_response.facets,
+        next_cursor: filter_response.next_cursor.map(|c| c.encode()),
+        search_id: None,
+        personalized: false,
     };
     
-    services.cache.set(&cache_key, &response, 300).await?;
+    if cacheable(&page) {
+        services.cache.set(&cache_key, &response, 300).await?;
+    }
+    response.search_id = Some(log.record(&services, response.total));
     
     Ok(HttpResponse::Ok().json(response))
 }
@@ -133,20 +400,21 @@ pub async fn advanced_search_programs(
     filter_req: web::Json<FilterRequest>,
     services: web::Data<AppServices>,
 ) -> Result<HttpResponse, ApiError> {
-    
-    let cache_key = format!(
-        "advanced:programs:{}:{}:{}",
-        query_params.query,
-        filter_req.status.as_ref().unwrap_or(&"none".to_string()),
-        filter_req.from
+    let page = page_request(filter_req.from, filter_req.size, &filter_req.cursor, filter_req.point_in_time)?;
+    let mut filters = filter_conditions(&filter_req)?;
+    let log = SearchLog::start("advanced", &query_params.query, &filters);
+    let cache_key = filter_cache_key(
+        &format!("advanced:programs:{}", query_params.query),
+        &filter_req,
     );
     
-    if let Some(cached_results) = services.cache.get::<SearchResultResponse<CourseSearchResult>>(&cache_key).await? {
+    if cacheable(&page)
+        && let Some(mut cached_results) = services.cache.get::<SearchResultResponse<CourseSearchResult>>(&cache_key).await?
+    {
+        cached_results.search_id = Some(log.record(&services, cached_results.total));
         return Ok(HttpResponse::Ok().json(cached_results));
     }
     
-    let mut filters: Vec<FilterCondition> = Vec::new();
-    
     if !query_params.query.is_empty() {
         filters.push(FilterCondition::Match {
             field: "title".to_string(),
@@ -154,43 +422,80 @@ pub async fn advanced_search_programs(
         });
     }
     
-    if let Some(status) = &filter_req.status {
-        filters.push(FilterCondition::Term {
-            field: "status".to_string(),
-            value: status.clone(),
-        });
-    }
-    
-    if filter_req.min_duration.is_some() || filter_req.max_duration.is_some() {
-        filters.push(FilterCondition::Range {
-            field: "total_duration_minutes".to_string(),
-            gte: filter_req.min_duration,
-            lte: filter_req.max_duration,
-            gt: None,
-            lt: None,
-        });
-    }
-    
     let filter_response = services.opensearch
-        .filter::<Course>(
-            "programs",
+        .filter::<CourseSearchResult>(
+            PROGRAMS_ALIAS,
             filters,
-            filter_req.from,
-            filter_req.size,
+            &page,
             filter_req.sort_by.as_deref(),
             filter_req.sort_order.as_deref(),
         )
         .await?;
     
-    let response = SearchResultResponse {
+    let mut response = SearchResultResponse {
         total: filter_response.total,
         results: filter_response.results,
         from: filter_req.from,
         size: filter_req.size,
         source: "opensearch".to_string(),
+        facets: filter_response.facets,
+        next_cursor: filter_response.next_cursor.map(|c| c.encode()),
+        search_id: None,
+        personalized: false,
     };
     
-    services.cache.set(&cache_key, &response, 300).await?;
+    if cacheable(&page) {
+        services.cache.set(&cache_key, &response, 300).await?;
+    }
+    response.search_id = Some(log.record(&services, response.total));
     
     Ok(HttpResponse::Ok().json(response))
 }
+
+#[get("/search/suggest")]
+pub async fn suggest(
+    query: web::Query<SuggestQuery>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let prefix = query.q.trim().to_lowercase();
+    if prefix.is_empty() {
+        return Ok(HttpResponse::Ok().json(Suggestions::default()));
+    }
+
+    let limit = query.limit.clamp(1, MAX_SUGGESTIONS_PER_TYPE);
+    let cache_key = format!("programs:suggest:{}:{}", limit, prefix);
+
+    if let Some(cached) = services.cache.get::<Suggestions>(&cache_key).await? {
+        return Ok(HttpResponse::Ok().json(cached));
+    }
+
+    let suggestions = services.opensearch.suggest(&prefix, limit).await?;
+
+    // Only prefixes typed repeatedly are worth holding on to.
+    let hits = services.cache
+        .increment(&format!("suggest:hits:{}", prefix), POPULAR_PREFIX_WINDOW_SECONDS)
+        .await?;
+    if hits >= POPULAR_PREFIX_THRESHOLD {
+        services.cache.set(&cache_key, &suggestions, SUGGEST_CACHE_TTL_SECONDS).await?;
+    }
+
+    Ok(HttpResponse::Ok().json(suggestions))
+}
+
+#[post("/search/events/{id}/click")]
+pub async fn record_search_click(
+    path: web::Path<String>,
+    req: web::Json<SearchClickRequest>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let search_id = path.into_inner();
+    let search_id = Uuid::parse_str(&search_id)
+        .map_err(|_| ApiError::BadRequest(format!("Invalid search id {}", search_id)))?;
+
+    let event = search_analytics::record_click(&services, search_id, &req.result_id, req.position).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "search_id": event.id,
+        "clicked_result_id": event.clicked_result_id
+    })))
+}
@@ -0,0 +1,265 @@
+use std::time::Duration;
+use actix_web::{post, web, HttpRequest, HttpResponse};
+use uuid::Uuid;
+use crate::dtos::uploads::{UploadIntentRequest, UploadIntentResponse};
+use crate::handlers::auth::current_user;
+use crate::handlers::images::schedule_cover_variants;
+use crate::models::index_retry::IndexTarget;
+use crate::models::programe::{Course, Lesson};
+use crate::models::upload::{split_reference, MediaKind, UploadIntent};
+use crate::service::{content_index, index_queue, video_probe, AppServices};
+use crate::traits::redis_trait::RedisCache;
+use crate::workers::documents::schedule_course_documents;
+use crate::error::ApiError;
+
+const UPLOAD_URL_TTL: Duration = Duration::from_secs(15 * 60);
+const UPLOAD_INTENT_TTL_SECONDS: u64 = 24 * 60 * 60;
+
+fn intent_key(upload_id: &str) -> String {
+    format!("uploads:{}", upload_id)
+}
+
+async fn apply_video_metadata(services: &AppServices, lesson: Lesson) -> Result<Lesson, ApiError> {
+    let (bucket, key) = split_reference(&lesson.video)
+        .ok_or_else(|| ApiError::BadRequest(format!("Invalid video reference {}", lesson.video)))?;
+
+    let metadata = video_probe::probe_video(services.storage.as_ref(), bucket, key).await?;
+
+    let lesson: Lesson = services.neo4j
+        .update_node("Lesson")
+        .node(&lesson.id)
+        .prop("duration_minutes", metadata.duration_minutes())
+        .prop("video_duration_seconds", metadata.duration_seconds)
+        .prop("video_width", metadata.width)
+        .prop("video_height", metadata.height)
+        .prop("video_codec", metadata.codec.as_str())
+        .exec()
+        .await?;
+
+    let courses: Vec<Course> = services.neo4j
+        .query_nodes(
+            "MATCH (m:Module)-[:HAS_LESSON]->(:Lesson {id: $lesson_id})
+             WITH DISTINCT m
+             OPTIONAL MATCH (m)-[:HAS_LESSON]->(l:Lesson)
+             WITH m, sum(toInteger(coalesce(l.duration_minutes, 0))) AS module_total
+             SET m.module_duration_minutes = module_total
+             WITH m
+             MATCH (c:Course)-[:HAS_MODULE]->(m)
+             WITH DISTINCT c
+             OPTIONAL MATCH (c)-[:HAS_MODULE]->(cm:Module)
+             WITH c, sum(toInteger(coalesce(cm.module_duration_minutes, 0))) AS course_total
+             SET c.total_duration_minutes = course_total
+             RETURN c"
+        )
+        .param("lesson_id", lesson.id.clone())
+        .fetch_key("c")
+        .fetch()
+        .await?;
+
+    for course in &courses {
+        index_queue::index_course(services, course).await;
+        services.kafka.publish_cache_invalidation("course_updated", &course.id).await?;
+    }
+
+    index_queue::sync_all(services, IndexTarget::Module, &content_index::lesson_module_ids(services, &lesson.id).await?).await;
+    index_queue::sync_or_queue(services, IndexTarget::Lesson, &lesson.id).await;
+
+    Ok(lesson)
+}
+
+async fn target_exists(services: &AppServices, kind: MediaKind, target_id: &str) -> Result<bool, ApiError> {
+    match kind {
+        MediaKind::Video => {
+            let lessons: Vec<Lesson> = services.neo4j
+                .query_nodes("MATCH (l:Lesson {id: $id}) RETURN l")
+                .param("id", target_id.to_string())
+                .fetch_key("l")
+                .fetch()
+                .await?;
+            Ok(!lessons.is_empty())
+        }
+        MediaKind::Cover | MediaKind::Document => {
+            let courses: Vec<Course> = services.neo4j
+                .query_nodes("MATCH (c:Course {id: $id}) RETURN c")
+                .param("id", target_id.to_string())
+                .fetch_key("c")
+                .fetch()
+                .await?;
+            Ok(!courses.is_empty())
+        }
+    }
+}
+
+pub async fn validate_upload(
+    services: &AppServices,
+    body: &UploadIntentRequest,
+    max_size_bytes: i64,
+) -> Result<&'static str, ApiError> {
+    let ext = body.kind.extension_for(&body.content_type).ok_or_else(|| {
+        ApiError::BadRequest(format!(
+            "Content type {} is not allowed for {:?} uploads",
+            body.content_type, body.kind
+        ))
+    })?;
+
+    if body.size_bytes <= 0 || body.size_bytes > max_size_bytes {
+        return Err(ApiError::BadRequest(format!(
+            "size_bytes must be between 1 and {}",
+            max_size_bytes
+        )));
+    }
+
+    if !target_exists(services, body.kind, &body.target_id).await? {
+        return Err(ApiError::NotFound(format!(
+            "{} {} not found",
+            body.kind.target_label(),
+            body.target_id
+        )));
+    }
+
+    Ok(ext)
+}
+
+pub async fn attach_media(
+    services: &web::Data<AppServices>,
+    kind: MediaKind,
+    target_id: &str,
+    reference: &str,
+) -> Result<serde_json::Value, ApiError> {
+    let attached = match kind {
+        MediaKind::Video => {
+            let lesson: Lesson = services.neo4j
+                .update_node("Lesson")
+                .node(target_id)
+                .prop("video", reference)
+                .exec()
+                .await?;
+
+            let lesson = match apply_video_metadata(services, lesson.clone()).await {
+                Ok(updated) => updated,
+                Err(e) => {
+                    eprintln!("⚠ Could not read metadata for video {}: {}", reference, e);
+                    lesson
+                }
+            };
+
+            services.kafka.publish_cache_invalidation("lesson_updated", &lesson.id).await?;
+            services.cache.delete_all("lessons:*").await?;
+            services.cache.delete_all("modules:*").await?;
+            serde_json::to_value(lesson)?
+        }
+        MediaKind::Cover | MediaKind::Document => {
+            let cypher = if kind == MediaKind::Cover {
+                "MATCH (c:Course {id: $id}) SET c.cover = $reference RETURN c"
+            } else {
+                "MATCH (c:Course {id: $id})
+                 SET c.documents = coalesce(c.documents, []) + $reference
+                 RETURN c"
+            };
+
+            let courses: Vec<Course> = services.neo4j
+                .query_nodes(cypher)
+                .param("id", target_id.to_string())
+                .param("reference", reference.to_string())
+                .fetch_key("c")
+                .fetch()
+                .await?;
+
+            let course = courses
+                .into_iter()
+                .next()
+                .ok_or_else(|| ApiError::NotFound(format!("Course {} not found", target_id)))?;
+
+            if kind == MediaKind::Cover {
+                schedule_cover_variants(services, &course.cover);
+            } else {
+                schedule_course_documents(services, &course.id);
+            }
+
+            index_queue::index_course(services, &course).await;
+            services.kafka.publish_cache_invalidation("course_updated", &course.id).await?;
+            serde_json::to_value(course)?
+        }
+    };
+
+    services.cache.delete_all("programs:*").await?;
+
+    Ok(attached)
+}
+
+#[post("/intents")]
+pub async fn create_upload_intent(
+    req: HttpRequest,
+    body: web::Json<UploadIntentRequest>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let user = current_user(&req, &services).await?;
+    let body = body.into_inner();
+
+    let ext = validate_upload(&services, &body, body.kind.max_size_bytes()).await?;
+
+    let intent = UploadIntent {
+        id: Uuid::new_v4().to_string(),
+        kind: body.kind,
+        bucket: body.kind.bucket().to_string(),
+        key: format!("{}/{}.{}", body.target_id, Uuid::new_v4(), ext),
+        target_id: body.target_id,
+        content_type: body.content_type,
+        size_bytes: body.size_bytes,
+        created_by: user.id.to_string(),
+    };
+
+    let upload_url = services.storage
+        .presign_put(&intent.bucket, &intent.key, &intent.content_type, intent.size_bytes, UPLOAD_URL_TTL)
+        .await?;
+
+    services.cache.set(&intent_key(&intent.id), &intent, UPLOAD_INTENT_TTL_SECONDS).await?;
+
+    Ok(HttpResponse::Created().json(UploadIntentResponse {
+        upload_id: intent.id.clone(),
+        upload_url,
+        method: "PUT".to_string(),
+        headers: serde_json::json!({
+            "Content-Type": intent.content_type,
+            "Content-Length": intent.size_bytes.to_string(),
+        }),
+        bucket: intent.bucket,
+        key: intent.key,
+        expires_in_seconds: UPLOAD_URL_TTL.as_secs(),
+    }))
+}
+
+#[post("/{id}/confirm")]
+pub async fn confirm_upload(
+    req: HttpRequest,
+    path: web::Path<String>,
+    services: web::Data<AppServices>,
+) -> Result<HttpResponse, ApiError> {
+    let upload_id = path.into_inner();
+    let user = current_user(&req, &services).await?;
+
+    let intent = services.cache
+        .get::<UploadIntent>(&intent_key(&upload_id))
+        .await?
+        .filter(|i| i.created_by == user.id.to_string())
+        .ok_or_else(|| ApiError::NotFound(format!("Upload {} not found", upload_id)))?;
+
+    let exists = services.storage
+        .object_exists(&intent.bucket, &intent.key)
+        .await?;
+
+    if !exists {
+        return Err(ApiError::BadRequest("Object has not been uploaded yet".to_string()));
+    }
+
+    let reference = intent.reference();
+
+    let attached = attach_media(&services, intent.kind, &intent.target_id, &reference).await?;
+    services.cache.delete(&intent_key(&upload_id)).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "upload_id": upload_id,
+        "reference": reference,
+        "attached_to": attached
+    })))
+}
@@ -2,7 +2,8 @@ use std::io;
 use actix_web::{http, web, App, HttpServer};
 use config::Config;
 
-use crate::service::{jwt_service::JwtService, kafka_service::KafkaService, neo4j_service::Neo4jService, opensearch::OpenSearchService, postgres_service::PostgresService, redis_cache_service::CacheService, s3_service::S3Service, AppServices};
+use crate::service::{email_channel::EmailChannel, jwt_service::JwtService, kafka_service::KafkaService, neo4j_service::Neo4jService, notification_service::NotificationService, opensearch::OpenSearchService, postgres_service::PostgresService, redis_cache_service::CacheService, build_storage, AppServices};
+use crate::traits::notification_channel::NotificationChannel;
 
 pub mod config;
 pub mod error;
@@ -13,28 +14,49 @@ pub mod handlers;
 pub mod models;
 pub mod routes;
 pub mod middleware;
+pub mod workers;
 
 #[actix_web::main]
 async fn main() -> io::Result<()> {
     let config = Config::new().await;
+
+    let mut channels: Vec<Box<dyn NotificationChannel>> = Vec::new();
+    if let Some(smtp) = &config.smtp {
+        channels.push(Box::new(EmailChannel::new(smtp.clone())));
+    }
+
+    let (storage, local_storage) = build_storage(&config);
+
     let services = AppServices {
         cache: CacheService::new(config.redis.clone()),
         neo4j: Neo4jService::new(config.neo4j.clone()),
         kafka: KafkaService::new(config.kafka_producer.clone()),
         postgress: PostgresService::new(config.postgres.clone()),
-        s3: S3Service::new(config.s3_client.clone()),
-        opensearch: OpenSearchService::new(config.opensearch.clone()),
-        jwt_service: JwtService::new(config.jwt_secret.clone(), config.encryption_key.clone().into_bytes())
+        storage,
+        local_storage,
+        opensearch: OpenSearchService::new(config.opensearch.clone(), config.synonyms_path.clone()),
+        jwt_service: JwtService::new(config.jwt_secret.clone(), config.encryption_key.clone().into_bytes()),
+        notifications: NotificationService::new(channels),
     };
     let app_state = web::Data::new(config);
     let app_services = web::Data::new(services);
+
+    actix_web::rt::spawn(workers::notifications::run_event_consumer(
+        app_services.clone(),
+        app_state.kafka_consumer.clone(),
+    ));
+    actix_web::rt::spawn(workers::notifications::run_deadline_reminders(app_services.clone()));
+    actix_web::rt::spawn(workers::uploads::run_upload_cleanup(app_services.clone()));
+    actix_web::rt::spawn(workers::search_index::check_programs_mapping(app_services.clone()));
+    actix_web::rt::spawn(workers::search_index::run_index_retries(app_services.clone()));
     
 
     HttpServer::new(move || {
         let cors = actix_cors::Cors::default()
             .allowed_origin("http://localhost:5173")
             .allowed_methods(vec!["GET", "POST", "PUT", "DELETE", "OPTIONS"])
-            .allowed_headers(vec![http::header::AUTHORIZATION, http::header::CONTENT_TYPE])
+            .allowed_headers(vec![http::header::AUTHORIZATION, http::header::CONTENT_TYPE, http::header::RANGE])
+            .expose_headers(vec![http::header::CONTENT_RANGE, http::header::ACCEPT_RANGES, http::header::CONTENT_LENGTH])
             .supports_credentials()
             .max_age(3600);
         
@@ -49,6 +71,13 @@ async fn main() -> io::Result<()> {
                     .configure(routes::modules::modules_routes)
                     .configure(routes::lessons::lessons_routes)
                     .configure(routes::search::program_search_routes)
+                    .configure(routes::cohorts::cohorts_routes)
+                    .configure(routes::uploads::uploads_routes)
+                    .configure(routes::media::media_routes)
+                    .configure(routes::storage::storage_routes)
+                    .configure(routes::images::images_routes)
+                    .configure(routes::admin::admin_routes)
+                    .configure(routes::me::me_routes)
             )
     })
     .bind("0.0.0.0:9090")?
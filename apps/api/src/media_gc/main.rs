@@ -0,0 +1,73 @@
+use std::env;
+use chrono::Duration;
+use api::config::Config;
+use api::service::build_storage;
+use api::service::media_gc::{reconcile_media, ReconcileOptions, DEFAULT_GRACE_PERIOD_HOURS};
+use api::service::neo4j_service::Neo4jService;
+
+fn parse_args() -> ReconcileOptions {
+    let mut delete = false;
+    let mut grace_hours = DEFAULT_GRACE_PERIOD_HOURS;
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--delete" => delete = true,
+            "--grace-hours" => {
+                grace_hours = args
+                    .next()
+                    .and_then(|v| v.parse().ok())
+                    .expect("--grace-hours expects a number of hours");
+            }
+            other => panic!("Unknown argument {} (expected --delete, --grace-hours <hours>)", other),
+        }
+    }
+
+    ReconcileOptions {
+        delete,
+        grace_period: Duration::hours(grace_hours),
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let options = parse_args();
+    let config = Config::new().await;
+    let (storage, _) = build_storage(&config);
+    let neo4j = Neo4jService::new(config.neo4j.clone());
+
+    println!(
+        "Reconciling media buckets ({})...",
+        if options.delete { "deleting expired orphans" } else { "report only" }
+    );
+
+    let report = reconcile_media(storage.as_ref(), &neo4j, &options).await?;
+
+    for orphan in &report.orphans {
+        println!(
+            " {} orphan {}/{} ({} bytes)",
+            if orphan.deleted { "✗ deleted" } else { "→" },
+            orphan.bucket,
+            orphan.key,
+            orphan.size
+        );
+    }
+    for dangling in &report.dangling_references {
+        println!(
+            " ⚠ {} {} {} points to missing {}",
+            dangling.label, dangling.node_id, dangling.field, dangling.reference
+        );
+    }
+
+    println!(
+        "\nScanned {} objects: {} referenced, {} orphaned, {} dangling references, {} deleted ({} bytes reclaimed)",
+        report.scanned_objects,
+        report.referenced_objects,
+        report.orphans.len(),
+        report.dangling_references.len(),
+        report.deleted_objects,
+        report.reclaimed_bytes
+    );
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use sqlx::types::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct SearchEvent {
+    pub id: Uuid,
+    pub endpoint: String,
+    pub query: String,
+    pub normalized_query: String,
+    pub filters: Option<String>,
+    pub result_count: i64,
+    pub latency_ms: i64,
+    pub clicked_result_id: Option<String>,
+    pub clicked_position: Option<i32>,
+    pub clicked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct QueryStat {
+    pub query: String,
+    pub endpoint: String,
+    pub searches: i64,
+    pub zero_result_searches: i64,
+    pub avg_results: f64,
+    pub avg_latency_ms: f64,
+    pub clicks: i64,
+    pub click_through_rate: f64,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ZeroResultQuery {
+    pub query: String,
+    pub endpoint: String,
+    pub searches: i64,
+    pub last_searched_at: NaiveDateTime,
+}
@@ -0,0 +1,33 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use sqlx::types::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Cohort {
+    pub id: Uuid,
+    pub course_id: String,
+    pub name: String,
+    pub starts_at: NaiveDateTime,
+    pub ends_at: NaiveDateTime,
+    pub capacity: i32,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct CohortMember {
+    pub id: Uuid,
+    pub cohort_id: Uuid,
+    pub user_id: Uuid,
+    pub status: String,
+    pub joined_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct CohortModuleSchedule {
+    pub id: Uuid,
+    pub cohort_id: Uuid,
+    pub module_id: String,
+    pub release_at: NaiveDateTime,
+    pub due_at: Option<NaiveDateTime>,
+}
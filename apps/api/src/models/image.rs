@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::upload::split_reference;
+
+pub const COVER_BUCKET: &str = "images";
+pub const VARIANT_WIDTHS: [u32; 3] = [320, 640, 1280];
+const VARIANT_PREFIX: &str = "variants";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormat {
+    Jpeg,
+    Webp,
+}
+
+impl ImageFormat {
+    pub const ALL: [ImageFormat; 2] = [ImageFormat::Webp, ImageFormat::Jpeg];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "jpeg",
+            ImageFormat::Webp => "webp",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Webp => "image/webp",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CoverVariant {
+    pub width: u32,
+    pub format: ImageFormat,
+    pub url: String,
+}
+
+/// Returns the key of the original cover inside the images bucket.
+pub fn cover_key(cover: &str) -> Option<&str> {
+    match split_reference(cover) {
+        Some((COVER_BUCKET, key)) => Some(key),
+        Some(_) => None,
+        None if !cover.is_empty() => Some(cover),
+        None => None,
+    }
+}
+
+/// `abc/cover.jpeg` at version `9f2c` as a 640px WebP lives at `variants/abc/cover/9f2c/w640.webp`,
+/// so replacing the original never serves a variant rendered from the old one.
+pub fn variant_key(original_key: &str, version: &str, width: u32, format: ImageFormat) -> String {
+    let stem = original_key
+        .rsplit_once('.')
+        .map(|(stem, _)| stem)
+        .unwrap_or(original_key);
+    format!("{}/{}/{}/w{}.{}", VARIANT_PREFIX, stem, version, width, format.extension())
+}
+
+pub fn is_variant_key(key: &str) -> bool {
+    key.starts_with(&format!("{}/", VARIANT_PREFIX))
+}
+
+pub fn variant_url(original_key: &str, width: u32, format: ImageFormat) -> String {
+    format!("/api/images/{}?width={}&format={}", original_key, width, format.extension())
+}
+
+/// The variant URL for one version of the original; its content never changes.
+pub fn pinned_variant_url(original_key: &str, version: &str, width: u32, format: ImageFormat) -> String {
+    format!("{}&v={}", variant_url(original_key, width, format), version)
+}
+
+pub fn cover_variants(cover: &str) -> Vec<CoverVariant> {
+    let Some(key) = cover_key(cover) else {
+        return vec![];
+    };
+
+    VARIANT_WIDTHS
+        .iter()
+        .flat_map(|&width| {
+            ImageFormat::ALL.iter().map(move |&format| CoverVariant {
+                width,
+                format,
+                url: variant_url(key, width, format),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,42 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+
+/// What a queued retry re-syncs: the course in the programs index, or a module
+/// or lesson in the content index.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum IndexTarget {
+    Course,
+    Module,
+    Lesson,
+}
+
+impl IndexTarget {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IndexTarget::Course => "course",
+            IndexTarget::Module => "module",
+            IndexTarget::Lesson => "lesson",
+        }
+    }
+
+    pub fn parse(target: &str) -> Option<Self> {
+        match target {
+            "course" => Some(IndexTarget::Course),
+            "module" => Some(IndexTarget::Module),
+            "lesson" => Some(IndexTarget::Lesson),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct IndexRetry {
+    pub target: String,
+    pub target_id: String,
+    pub attempts: i32,
+    pub last_error: String,
+    pub next_attempt_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
@@ -1,3 +1,12 @@
 pub mod programe;
 pub mod search;
+pub mod search_query;
+pub mod index_retry;
+pub mod analytics;
 pub mod auth;
+pub mod progress;
+pub mod cohort;
+pub mod notification;
+pub mod upload;
+pub mod video;
+pub mod image;
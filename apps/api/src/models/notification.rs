@@ -0,0 +1,32 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use sqlx::types::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Notification {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: String,
+    pub title: String,
+    pub body: String,
+    pub resource_id: Option<String>,
+    #[serde(skip_serializing)]
+    pub dedup_key: Option<String>,
+    pub read_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, FromRow)]
+pub struct UnreadCount {
+    pub unread: i64,
+}
+
+#[derive(Debug, FromRow)]
+pub struct UpcomingDeadline {
+    pub user_id: Uuid,
+    pub schedule_id: Uuid,
+    pub module_id: String,
+    pub cohort_name: String,
+    pub due_at: NaiveDateTime,
+}
@@ -1,7 +1,7 @@
 use neo4rs::Error;
 use serde::{Serialize, Deserialize};
 
-use crate::{error::ApiError, traits::from_node::FromNode};
+use crate::{error::ApiError, models::{image::{cover_variants, CoverVariant}, video::VideoMetadata}, traits::from_node::FromNode};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Course {
@@ -15,6 +15,8 @@ pub struct Course {
     pub instructor: String,
     pub featured: bool,
     pub cover: String,
+    #[serde(default)]
+    pub cover_variants: Vec<CoverVariant>,
     pub prerequisites: Vec<String>,
     pub documents: Vec<String>,
     pub total_duration_minutes: i32,
@@ -36,13 +38,17 @@ pub struct Lesson {
     pub duration_minutes: i32,
     pub prerequisites: Vec<String>,
     pub completed: bool,
-    pub video: String
+    pub video: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub video_metadata: Option<VideoMetadata>,
 }
 
 
 
 impl FromNode for Course {
     fn from_node(node: &neo4rs::Node) -> Result<Self, ApiError> {
+        let cover: String = node.get("cover").unwrap_or_default();
+
         Ok(Self {
             id: node.get("id").map_err(|_| ApiError::Internal("missing course id".to_string()))?,
             title: node.get("title").unwrap_or_default(),
@@ -53,16 +59,11 @@ impl FromNode for Course {
             rating: node.get("rating").unwrap_or(0.0),
             instructor: node.get("instructor").map_err(|_| ApiError::Internal("missing category".to_string()))?,
             featured: node.get("featured").unwrap_or(false),
-            cover: node.get("cover").unwrap_or_default(),
-            prerequisites: match node.get::<Vec<String>>("prerequisites") {
-                Ok(pre) => pre,
-                Err(_) => vec![], 
-            },
-            documents: match node.get::<Vec<String>>("documents") {
-                Ok(docs) => docs,
-                Err(_) => vec![], 
-            },
-            total_duration_minutes: 0,
+            cover_variants: cover_variants(&cover),
+            cover,
+            prerequisites: node.get::<Vec<String>>("prerequisites").unwrap_or_default(),
+            documents: node.get::<Vec<String>>("documents").unwrap_or_default(),
+            total_duration_minutes: node.get("total_duration_minutes").unwrap_or(0),
         })
     }
 }
@@ -77,7 +78,7 @@ impl FromNode for Module {
                 .map_err(|_| ApiError::Neo4j(Error::ConversionError))?
                 .parse::<i32>()
                 .map_err(|_| ApiError::Neo4j(Error::ConversionError))?,
-            module_duration_minutes: 0,
+            module_duration_minutes: node.get("module_duration_minutes").unwrap_or(0),
         })
     }
 }
@@ -93,12 +94,18 @@ impl FromNode for Lesson {
                 .parse::<i32>()
                 .map_err(|_| ApiError::Neo4j(Error::ConversionError))?,
             duration_minutes: node.get("duration_minutes").unwrap_or(0),
-            prerequisites: match node.get::<Vec<String>>("prerequisites") {
-                Ok(pre) => pre,
-                Err(_) => vec![], 
-            },
+            prerequisites: node.get::<Vec<String>>("prerequisites").unwrap_or_default(),
             completed: node.get("completed").unwrap_or(false),
-            video: node.get::<String>("video").map_err(|_| ApiError::Neo4j(Error::ConversionError))?
+            video: node.get::<String>("video").map_err(|_| ApiError::Neo4j(Error::ConversionError))?,
+            video_metadata: match node.get::<f64>("video_duration_seconds") {
+                Ok(duration_seconds) => Some(VideoMetadata {
+                    duration_seconds,
+                    width: node.get("video_width").unwrap_or(0),
+                    height: node.get("video_height").unwrap_or(0),
+                    codec: node.get("video_codec").unwrap_or_default(),
+                }),
+                Err(_) => None,
+            },
         })
     }
 }
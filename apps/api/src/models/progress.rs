@@ -0,0 +1,28 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use sqlx::types::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Enrollment {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub course_id: String,
+    pub enrolled_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct LessonProgress {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub course_id: String,
+    pub lesson_id: String,
+    pub completed_at: NaiveDateTime,
+}
+
+#[derive(Debug, FromRow)]
+pub struct CourseLessonCompletions {
+    pub user_id: Uuid,
+    pub course_id: String,
+    pub completed_lessons: i64,
+}
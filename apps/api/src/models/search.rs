@@ -1,16 +1,384 @@
-use serde::Serialize;
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
+use crate::models::programe::Course;
 
-#[derive(Debug)]
+/// Fields of the programs index a filter may reference.
+pub const FILTERABLE_FIELDS: [&str; 14] = [
+    "id",
+    "title",
+    "description",
+    "status",
+    "category",
+    "level",
+    "rating",
+    "instructor",
+    "featured",
+    "cover",
+    "prerequisites",
+    "documents",
+    "total_duration_minutes",
+    "title.keyword",
+];
+const MAX_FILTER_DEPTH: usize = 8;
+/// Inline elements safe to wrap highlighted terms in.
+const HIGHLIGHT_TAGS: [&str; 6] = ["em", "mark", "strong", "b", "i", "span"];
+
+/// A filter tree. As JSON each node is tagged by `type`, e.g.
+/// `{"type": "or", "conditions": [{"type": "term", "field": "category", "value": "Rust"}, ...]}`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum FilterCondition {
     Term { field: String, value: String },
     Range { field: String, gte: Option<i64>, lte: Option<i64>, gt: Option<i64>, lt: Option<i64> },
+    FloatRange { field: String, gte: Option<f64>, lte: Option<f64>, gt: Option<f64>, lt: Option<f64> },
     Match { field: String, value: String },
     Terms { field: String, values: Vec<String> },
+    Exists { field: String },
+    Prefix { field: String, value: String },
+    Wildcard { field: String, value: String },
+    And { conditions: Vec<FilterCondition> },
+    Or { conditions: Vec<FilterCondition> },
+    Not { condition: Box<FilterCondition> },
+}
+
+impl FilterCondition {
+    /// The one field this condition constrains; composites qualify when every branch shares it.
+    pub fn field(&self) -> Option<&str> {
+        match self {
+            FilterCondition::Term { field, .. }
+            | FilterCondition::Range { field, .. }
+            | FilterCondition::FloatRange { field, .. }
+            | FilterCondition::Match { field, .. }
+            | FilterCondition::Terms { field, .. }
+            | FilterCondition::Exists { field }
+            | FilterCondition::Prefix { field, .. }
+            | FilterCondition::Wildcard { field, .. } => Some(field),
+            FilterCondition::And { conditions } | FilterCondition::Or { conditions } => {
+                let first = conditions.first()?.field()?;
+                conditions
+                    .iter()
+                    .all(|c| c.field() == Some(first))
+                    .then_some(first)
+            }
+            FilterCondition::Not { condition } => condition.field(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        self.validate_at(1)
+    }
+
+    fn validate_at(&self, depth: usize) -> Result<(), String> {
+        if depth > MAX_FILTER_DEPTH {
+            return Err(format!("filters may nest at most {} levels deep", MAX_FILTER_DEPTH));
+        }
+
+        match self {
+            FilterCondition::And { conditions } | FilterCondition::Or { conditions } => {
+                if conditions.is_empty() {
+                    return Err("and/or filters need at least one condition".to_string());
+                }
+                conditions.iter().try_for_each(|c| c.validate_at(depth + 1))
+            }
+            FilterCondition::Not { condition } => condition.validate_at(depth + 1),
+            leaf => {
+                let field = leaf.field().unwrap_or_default();
+                if FILTERABLE_FIELDS.contains(&field) {
+                    Ok(())
+                } else {
+                    Err(format!("cannot filter on unknown field '{}'", field))
+                }
+            }
+        }
+    }
+}
+
+fn default_fragment_size() -> i64 {
+    150
+}
+
+fn default_number_of_fragments() -> i64 {
+    3
+}
+
+fn default_pre_tag() -> String {
+    "<em>".to_string()
+}
+
+fn default_post_tag() -> String {
+    "</em>".to_string()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HighlightOptions {
+    #[serde(default = "default_fragment_size")]
+    pub fragment_size: i64,
+    #[serde(default = "default_number_of_fragments")]
+    pub number_of_fragments: i64,
+    #[serde(default = "default_pre_tag")]
+    pub pre_tag: String,
+    #[serde(default = "default_post_tag")]
+    pub post_tag: String,
+}
+
+impl Default for HighlightOptions {
+    fn default() -> Self {
+        Self {
+            fragment_size: default_fragment_size(),
+            number_of_fragments: default_number_of_fragments(),
+            pre_tag: default_pre_tag(),
+            post_tag: default_post_tag(),
+        }
+    }
+}
+
+fn tag_name(tag: &str, closing: bool) -> Option<&str> {
+    let inner = tag.strip_prefix(if closing { "</" } else { "<" })?.strip_suffix('>')?;
+    let (name, attributes) = inner.split_once(' ').unwrap_or((inner, ""));
+
+    let name_ok = HIGHLIGHT_TAGS.iter().any(|allowed| allowed.eq_ignore_ascii_case(name));
+    let attributes_ok = attributes.is_empty()
+        || (!closing
+            && attributes
+                .strip_prefix("class=\"")
+                .and_then(|a| a.strip_suffix('"'))
+                .map(|class| class.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == ' '))
+                .unwrap_or(false));
+
+    (name_ok && attributes_ok).then_some(name)
+}
+
+impl HighlightOptions {
+    /// Fragments are HTML-encoded by OpenSearch, so the tags are the only raw markup
+    /// that reaches the client; they must be one of `HIGHLIGHT_TAGS` with at most a class.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(20..=1000).contains(&self.fragment_size) {
+            return Err("fragment_size must be between 20 and 1000".to_string());
+        }
+        if !(1..=10).contains(&self.number_of_fragments) {
+            return Err("number_of_fragments must be between 1 and 10".to_string());
+        }
+
+        match (tag_name(&self.pre_tag, false), tag_name(&self.post_tag, true)) {
+            (Some(open), Some(close)) if open.eq_ignore_ascii_case(close) => Ok(()),
+            _ => Err(format!(
+                "pre_tag and post_tag must be a matching pair like <mark class=\"hit\"> and </mark>, using one of {}",
+                HIGHLIGHT_TAGS.join(", ")
+            )),
+        }
+    }
+}
+
+/// Where the next page starts: the sort values of the last hit, plus the point-in-time
+/// context when the client asked for a consistent snapshot.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SearchCursor {
+    pub search_after: Vec<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pit_id: Option<String>,
+}
+
+impl SearchCursor {
+    pub fn encode(&self) -> String {
+        general_purpose::URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    pub fn decode(cursor: &str) -> Result<Self, String> {
+        general_purpose::URL_SAFE_NO_PAD
+            .decode(cursor)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<SearchCursor>(&bytes).ok())
+            .filter(|c| !c.search_after.is_empty())
+            .ok_or_else(|| "cursor is malformed".to_string())
+    }
+}
+
+pub struct PageRequest {
+    pub from: i64,
+    pub size: i64,
+    pub cursor: Option<SearchCursor>,
+    pub point_in_time: bool,
 }
 
 #[derive(Debug, Serialize)]
 pub struct SearchResponse<T> {
     pub total: i64,
     pub results: Vec<T>,
+    pub facets: Option<SearchFacets>,
+    pub next_cursor: Option<SearchCursor>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ProgramsIndex {
+    pub name: String,
+    pub version: u32,
+    pub aliased: bool,
+    pub checksum: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FacetBucket {
+    pub key: String,
+    pub count: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SearchFacets {
+    pub status: Vec<FacetBucket>,
+    pub category: Vec<FacetBucket>,
+    pub level: Vec<FacetBucket>,
+    pub instructor: Vec<FacetBucket>,
+    pub prerequisites: Vec<FacetBucket>,
+    pub total_duration_minutes: Vec<FacetBucket>,
+    pub rating: Vec<FacetBucket>,
+    pub featured: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DocumentMatch {
+    pub document: String,
+    pub title: String,
+    pub page: i32,
+    pub summary: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fragments: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentKind {
+    Module,
+    Lesson,
+}
+
+/// Where a module or lesson sits in the catalog; lessons carry the module they are reached through.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContentPath {
+    pub course_id: String,
+    pub course_title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module_title: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContentDocument {
+    pub id: String,
+    pub kind: ContentKind,
+    pub title: String,
+    pub order: i64,
+    pub duration_minutes: i64,
+    pub paths: Vec<ContentPath>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LessonHit {
+    pub id: String,
+    pub title: String,
+    pub duration_minutes: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModuleHit {
+    pub id: String,
+    pub title: String,
+    pub matched: bool,
+    pub lessons: Vec<LessonHit>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Suggestion {
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Suggestions {
+    pub courses: Vec<Suggestion>,
+    pub lessons: Vec<Suggestion>,
+    pub categories: Vec<Suggestion>,
+    pub instructors: Vec<Suggestion>,
+}
+
+/// What personal ranking knows about the searcher. Completed courses are demoted and
+/// courses whose prerequisites are all completed are boosted.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Personalization {
+    pub level: Option<String>,
+    pub categories: Vec<String>,
+    pub completed_course_ids: Vec<String>,
+    pub ready_course_ids: Vec<String>,
+}
+
+impl Personalization {
+    pub fn is_empty(&self) -> bool {
+        self.level.is_none()
+            && self.categories.is_empty()
+            && self.completed_course_ids.is_empty()
+            && self.ready_course_ids.is_empty()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RelatedSignal {
+    SimilarContent,
+    SharedPrerequisites,
+    SameInstructor,
+    CompletedTogether,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RelatedReason {
+    pub signal: RelatedSignal,
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RelatedCourse {
+    pub course: Course,
+    pub score: f64,
+    pub reasons: Vec<RelatedReason>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn highlight(pre_tag: &str, post_tag: &str) -> HighlightOptions {
+        HighlightOptions {
+            pre_tag: pre_tag.to_string(),
+            post_tag: post_tag.to_string(),
+            ..HighlightOptions::default()
+        }
+    }
+
+    #[test]
+    fn accepts_inline_highlight_tags() {
+        assert!(HighlightOptions::default().validate().is_ok());
+        assert!(highlight("<mark class=\"hit\">", "</mark>").validate().is_ok());
+        assert!(highlight("<STRONG>", "</strong>").validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_tags_outside_the_allowlist() {
+        assert!(highlight("<script>", "</script>").validate().is_err());
+        assert!(highlight("<iframe>", "</iframe>").validate().is_err());
+        assert!(highlight("<style>", "</style>").validate().is_err());
+    }
+
+    #[test]
+    fn rejects_mismatched_tags_and_other_attributes() {
+        assert!(highlight("<em>", "</mark>").validate().is_err());
+        assert!(highlight("<em onclick=\"x()\">", "</em>").validate().is_err());
+        assert!(highlight("<em class=\"a\">", "</em class=\"a\">").validate().is_err());
+    }
 }
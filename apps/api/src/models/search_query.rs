@@ -0,0 +1,423 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::models::search::FilterCondition;
+
+const FIELDS: &str = "status, level, category, instructor, title, prerequisites, featured, duration, rating";
+
+/// A catalog query such as `level:beginner duration:<60 "async rust" -draft`,
+/// split into free text, exact phrases and filters.
+#[derive(Debug, Default)]
+pub struct ParsedQuery {
+    pub text: String,
+    pub phrases: Vec<String>,
+    pub excluded: Vec<String>,
+    pub filters: Vec<FilterCondition>,
+    pub excluded_filters: Vec<FilterCondition>,
+}
+
+impl ParsedQuery {
+    /// Plain free text with no syntax, as sent in the `query` field.
+    pub fn text(query: &str) -> Self {
+        Self {
+            text: query.trim().to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Words and phrases together, for the indices that only take a plain match.
+    pub fn free_text(&self) -> String {
+        std::iter::once(self.text.as_str())
+            .chain(self.phrases.iter().map(|p| p.as_str()))
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// `position` is the 0-based character offset into the query string.
+#[derive(Debug)]
+pub struct QueryParseError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for QueryParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+fn error<T>(position: usize, message: impl Into<String>) -> Result<T, QueryParseError> {
+    Err(QueryParseError {
+        position,
+        message: message.into(),
+    })
+}
+
+struct Bounds<T> {
+    gte: Option<T>,
+    lte: Option<T>,
+    gt: Option<T>,
+    lt: Option<T>,
+}
+
+fn number<T: FromStr>(value: &str, position: usize) -> Result<T, QueryParseError> {
+    value
+        .parse()
+        .or_else(|_| error(position, format!("expected a number, found '{}'", value)))
+}
+
+/// Accepts `60`, `=60`, `<60`, `<=60`, `>60`, `>=60` and `30..60`.
+fn bounds<T: FromStr + Copy>(value: &str, position: usize) -> Result<Bounds<T>, QueryParseError> {
+    if let Some((from, to)) = value.split_once("..") {
+        let to_position = position + from.chars().count() + 2;
+        return Ok(Bounds {
+            gte: Some(number(from, position)?),
+            lte: Some(number(to, to_position)?),
+            gt: None,
+            lt: None,
+        });
+    }
+
+    for (op, len) in [(">=", 2), ("<=", 2), (">", 1), ("<", 1), ("=", 1)] {
+        if let Some(rest) = value.strip_prefix(op) {
+            let n = Some(number(rest, position + len)?);
+            return Ok(match op {
+                ">=" => Bounds { gte: n, lte: None, gt: None, lt: None },
+                "<=" => Bounds { gte: None, lte: n, gt: None, lt: None },
+                ">" => Bounds { gte: None, lte: None, gt: n, lt: None },
+                "<" => Bounds { gte: None, lte: None, gt: None, lt: n },
+                _ => Bounds { gte: n, lte: n, gt: None, lt: None },
+            });
+        }
+    }
+
+    let n = Some(number(value, position)?);
+    Ok(Bounds { gte: n, lte: n, gt: None, lt: None })
+}
+
+fn condition(
+    field: &str,
+    field_position: usize,
+    value: &str,
+    value_position: usize,
+) -> Result<FilterCondition, QueryParseError> {
+    let term = |name: &str| FilterCondition::Term {
+        field: name.to_string(),
+        value: value.to_string(),
+    };
+
+    match field.to_lowercase().as_str() {
+        "status" => Ok(term("status")),
+        "level" => Ok(term("level")),
+        "category" => Ok(term("category")),
+        "instructor" => Ok(term("instructor")),
+        "title" => Ok(FilterCondition::Match {
+            field: "title".to_string(),
+            value: value.to_string(),
+        }),
+        "prereq" | "prerequisite" | "prerequisites" => Ok(FilterCondition::Terms {
+            field: "prerequisites".to_string(),
+            values: value
+                .split(',')
+                .map(|v| v.trim())
+                .filter(|v| !v.is_empty())
+                .map(|v| v.to_string())
+                .collect(),
+        }),
+        "featured" => match value.to_lowercase().as_str() {
+            "true" | "yes" => Ok(FilterCondition::Term {
+                field: "featured".to_string(),
+                value: "true".to_string(),
+            }),
+            "false" | "no" => Ok(FilterCondition::Term {
+                field: "featured".to_string(),
+                value: "false".to_string(),
+            }),
+            _ => error(value_position, format!("featured expects true or false, found '{}'", value)),
+        },
+        "duration" => {
+            let b = bounds::<i64>(value, value_position)?;
+            Ok(FilterCondition::Range {
+                field: "total_duration_minutes".to_string(),
+                gte: b.gte,
+                lte: b.lte,
+                gt: b.gt,
+                lt: b.lt,
+            })
+        }
+        "rating" => {
+            let b = bounds::<f64>(value, value_position)?;
+            Ok(FilterCondition::FloatRange {
+                field: "rating".to_string(),
+                gte: b.gte,
+                lte: b.lte,
+                gt: b.gt,
+                lt: b.lt,
+            })
+        }
+        _ => error(
+            field_position,
+            format!("unknown field '{}', expected one of {}", field, FIELDS),
+        ),
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn at_boundary(&self) -> bool {
+        self.peek().map(|c| c.is_whitespace()).unwrap_or(true)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().map(|c| c.is_whitespace()).unwrap_or(false) {
+            self.pos += 1;
+        }
+    }
+
+    fn take_while(&mut self, keep: impl Fn(char) -> bool) -> String {
+        let start = self.pos;
+        while self.peek().map(&keep).unwrap_or(false) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    /// Reads `"..."` starting at the opening quote; `\"` escapes a quote inside.
+    fn quoted(&mut self) -> Result<String, QueryParseError> {
+        let start = self.pos;
+        self.pos += 1;
+
+        let mut value = String::new();
+        loop {
+            match self.peek() {
+                None => return error(start, "unterminated quote"),
+                Some('"') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some('\\') if self.chars.get(self.pos + 1) == Some(&'"') => {
+                    value.push('"');
+                    self.pos += 2;
+                }
+                Some(c) => {
+                    value.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+
+        if !self.at_boundary() {
+            return error(self.pos, "expected a space after the closing quote");
+        }
+        if value.trim().is_empty() {
+            return error(start, "empty quotes");
+        }
+        Ok(value)
+    }
+}
+
+pub fn parse_query(input: &str) -> Result<ParsedQuery, QueryParseError> {
+    let mut parser = Parser {
+        chars: input.chars().collect(),
+        pos: 0,
+    };
+    let mut parsed = ParsedQuery::default();
+    let mut words: Vec<String> = Vec::new();
+
+    loop {
+        parser.skip_whitespace();
+        let Some(first) = parser.peek() else { break };
+
+        let start = parser.pos;
+        let negated = first == '-';
+        if negated {
+            parser.pos += 1;
+            if parser.at_boundary() {
+                return error(start, "expected a term after '-'");
+            }
+        }
+
+        if parser.peek() == Some('"') {
+            let phrase = parser.quoted()?;
+            if negated {
+                parsed.excluded.push(phrase);
+            } else {
+                parsed.phrases.push(phrase);
+            }
+            continue;
+        }
+
+        let field_position = parser.pos;
+        let name = parser.take_while(|c| c.is_alphanumeric() || c == '_');
+
+        if !name.is_empty() && parser.peek() == Some(':') {
+            parser.pos += 1;
+            let value_position = parser.pos;
+            let value = match parser.peek() {
+                Some('"') => parser.quoted()?,
+                _ => parser.take_while(|c| !c.is_whitespace()),
+            };
+            if value.is_empty() {
+                return error(value_position, format!("expected a value after '{}:'", name));
+            }
+
+            let filter = condition(&name, field_position, &value, value_position)?;
+            if negated {
+                parsed.excluded_filters.push(filter);
+            } else {
+                parsed.filters.push(filter);
+            }
+            continue;
+        }
+
+        let rest = parser.take_while(|c| !c.is_whitespace() && c != '"');
+        let word = format!("{}{}", name, rest);
+        if word.is_empty() {
+            return error(parser.pos, "unexpected character");
+        }
+        if negated {
+            parsed.excluded.push(word);
+        } else {
+            words.push(word);
+        }
+    }
+
+    parsed.text = words.join(" ");
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> ParsedQuery {
+        parse_query(input).unwrap_or_else(|e| panic!("{:?} failed to parse: {}", input, e))
+    }
+
+    fn error_at(input: &str) -> usize {
+        parse_query(input).expect_err(input).position
+    }
+
+    #[test]
+    fn splits_words_phrases_and_filters() {
+        let parsed = parse(r#"level:beginner  async "error handling" rust"#);
+
+        assert_eq!(parsed.text, "async rust");
+        assert_eq!(parsed.phrases, vec!["error handling"]);
+        assert!(matches!(
+            parsed.filters.as_slice(),
+            [FilterCondition::Term { field, value }] if field == "level" && value == "beginner"
+        ));
+        assert_eq!(parsed.free_text(), "async rust error handling");
+    }
+
+    #[test]
+    fn quoted_values_keep_spaces_and_escaped_quotes() {
+        let parsed = parse(r#"instructor:"Jane Doe" "say \"hi\"""#);
+
+        assert!(matches!(
+            parsed.filters.as_slice(),
+            [FilterCondition::Term { field, value }] if field == "instructor" && value == "Jane Doe"
+        ));
+        assert_eq!(parsed.phrases, vec![r#"say "hi""#]);
+    }
+
+    #[test]
+    fn field_names_are_case_insensitive_and_aliased() {
+        let parsed = parse("Category:Rust prereq:Basics,Ownership featured:yes title:actix");
+
+        assert!(matches!(
+            parsed.filters.as_slice(),
+            [
+                FilterCondition::Term { field: category, .. },
+                FilterCondition::Terms { field: prerequisites, values },
+                FilterCondition::Term { field: featured, value: featured_value },
+                FilterCondition::Match { field: title, .. },
+            ] if category == "category"
+                && prerequisites == "prerequisites"
+                && values == &["Basics", "Ownership"]
+                && featured == "featured"
+                && featured_value == "true"
+                && title == "title"
+        ));
+    }
+
+    #[test]
+    fn parses_range_operators() {
+        let parsed = parse("duration:<60 duration:>=30 duration:30..90 duration:45 rating:>4.5");
+
+        let ranges: Vec<[Option<i64>; 4]> = parsed
+            .filters
+            .iter()
+            .filter_map(|f| match f {
+                FilterCondition::Range { field, gte, lte, gt, lt } if field == "total_duration_minutes" => {
+                    Some([*gte, *lte, *gt, *lt])
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            ranges,
+            vec![
+                [None, None, None, Some(60)],
+                [Some(30), None, None, None],
+                [Some(30), Some(90), None, None],
+                [Some(45), Some(45), None, None],
+            ]
+        );
+        assert!(matches!(
+            parsed.filters.last(),
+            Some(FilterCondition::FloatRange { field, gt: Some(gt), gte: None, lte: None, lt: None })
+                if field == "rating" && *gt == 4.5
+        ));
+    }
+
+    #[test]
+    fn negation_excludes_words_phrases_and_filters() {
+        let parsed = parse(r#"rust -draft -"old version" -level:advanced"#);
+
+        assert_eq!(parsed.text, "rust");
+        assert_eq!(parsed.excluded, vec!["draft", "old version"]);
+        assert!(parsed.filters.is_empty());
+        assert!(matches!(
+            parsed.excluded_filters.as_slice(),
+            [FilterCondition::Term { field, value }] if field == "level" && value == "advanced"
+        ));
+    }
+
+    #[test]
+    fn plain_text_is_left_as_is() {
+        let parsed = ParsedQuery::text("  level:beginner \"rust\"  ");
+
+        assert_eq!(parsed.text, "level:beginner \"rust\"");
+        assert!(parsed.filters.is_empty());
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_character() {
+        assert_eq!(error_at(r#"rust "unterminated"#), 5);
+        assert_eq!(error_at(r#""closed"x"#), 8);
+        assert_eq!(error_at(r#"a """#), 2);
+        assert_eq!(error_at("rust - draft"), 5);
+        assert_eq!(error_at("rust colour:red"), 5);
+        assert_eq!(error_at("level: beginner"), 6);
+        assert_eq!(error_at("featured:maybe"), 9);
+        assert_eq!(error_at("duration:<abc"), 10);
+        assert_eq!(error_at("duration:30..x"), 13);
+    }
+
+    #[test]
+    fn error_offsets_count_characters_not_bytes() {
+        assert_eq!(error_at("café colour:red"), 5);
+    }
+}
@@ -0,0 +1,126 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+use sqlx::types::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaKind {
+    Video,
+    Cover,
+    Document,
+}
+
+impl MediaKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MediaKind::Video => "video",
+            MediaKind::Cover => "cover",
+            MediaKind::Document => "document",
+        }
+    }
+
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "video" => Some(MediaKind::Video),
+            "cover" => Some(MediaKind::Cover),
+            "document" => Some(MediaKind::Document),
+            _ => None,
+        }
+    }
+
+    pub fn bucket(&self) -> &'static str {
+        match self {
+            MediaKind::Video => "videos",
+            MediaKind::Cover => "images",
+            MediaKind::Document => "documents",
+        }
+    }
+
+    pub fn target_label(&self) -> &'static str {
+        match self {
+            MediaKind::Video => "Lesson",
+            MediaKind::Cover | MediaKind::Document => "Course",
+        }
+    }
+
+    pub fn max_size_bytes(&self) -> i64 {
+        match self {
+            MediaKind::Video => 5 * 1024 * 1024 * 1024,
+            MediaKind::Cover => 10 * 1024 * 1024,
+            MediaKind::Document => 50 * 1024 * 1024,
+        }
+    }
+
+    pub fn max_multipart_size_bytes(&self) -> i64 {
+        match self {
+            MediaKind::Video => 50 * 1024 * 1024 * 1024,
+            _ => self.max_size_bytes(),
+        }
+    }
+
+    pub fn extension_for(&self, content_type: &str) -> Option<&'static str> {
+        match (self, content_type) {
+            (MediaKind::Video, "video/mp4") => Some("mp4"),
+            (MediaKind::Video, "video/quicktime") => Some("mov"),
+            (MediaKind::Video, "video/webm") => Some("webm"),
+            (MediaKind::Cover, "image/jpeg") => Some("jpeg"),
+            (MediaKind::Cover, "image/png") => Some("png"),
+            (MediaKind::Cover, "image/webp") => Some("webp"),
+            (MediaKind::Document, "application/pdf") => Some("pdf"),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UploadIntent {
+    pub id: String,
+    pub kind: MediaKind,
+    pub target_id: String,
+    pub bucket: String,
+    pub key: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub created_by: String,
+}
+
+impl UploadIntent {
+    pub fn reference(&self) -> String {
+        format!("{}/{}", self.bucket, self.key)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct UploadSession {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: String,
+    pub target_id: String,
+    pub bucket: String,
+    pub object_key: String,
+    #[serde(skip_serializing)]
+    pub s3_upload_id: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub part_size: i64,
+    pub status: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl UploadSession {
+    pub fn part_count(&self) -> i64 {
+        (self.size_bytes + self.part_size - 1) / self.part_size
+    }
+
+    pub fn reference(&self) -> String {
+        format!("{}/{}", self.bucket, self.object_key)
+    }
+}
+
+pub fn split_reference(reference: &str) -> Option<(&str, &str)> {
+    reference
+        .split_once('/')
+        .filter(|(bucket, key)| !bucket.is_empty() && !key.is_empty())
+}
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct VideoMetadata {
+    pub duration_seconds: f64,
+    pub width: i64,
+    pub height: i64,
+    pub codec: String,
+}
+
+impl VideoMetadata {
+    pub fn duration_minutes(&self) -> i32 {
+        (self.duration_seconds / 60.0).ceil() as i32
+    }
+}
@@ -0,0 +1,43 @@
+use std::env;
+use api::config::Config;
+use api::service::neo4j_service::Neo4jService;
+use api::service::opensearch::OpenSearchService;
+use api::service::programs_reindex::{programs_index_status, reindex_programs};
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let mut if_needed = false;
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--if-needed" => if_needed = true,
+            other => panic!("Unknown argument {} (expected --if-needed)", other),
+        }
+    }
+
+    let config = Config::new().await;
+    let opensearch = OpenSearchService::new(config.opensearch.clone(), config.synonyms_path.clone());
+    let neo4j = Neo4jService::new(config.neo4j.clone());
+
+    let status = programs_index_status(&opensearch).await?;
+    println!(
+        "{} → {} (checksum {}, expected {})",
+        status.alias,
+        status.index.as_deref().unwrap_or("missing"),
+        status.checksum.as_deref().unwrap_or("none"),
+        status.expected_checksum
+    );
+
+    if if_needed && status.index.is_some() && !status.reindex_required {
+        println!("Mapping is current, nothing to do");
+        return Ok(());
+    }
+
+    let report = reindex_programs(&opensearch, &neo4j).await?;
+
+    println!("✓ Indexed {} courses into {}", report.courses, report.index);
+    for index in &report.deleted_indices {
+        println!(" ✗ deleted {}", index);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,21 @@
+use actix_web::web;
+
+use crate::{handlers::admin, middleware::auth_middleware::AuthMiddleware};
+
+pub fn admin_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/admin")
+            .wrap(AuthMiddleware::new())
+            .service(admin::reconcile_media_objects)
+            .service(admin::reindex_documents)
+            .service(admin::reindex_content)
+            .service(admin::programs_search_index_status)
+            .service(admin::reindex_programs_search)
+            .service(admin::search_index_retries)
+            .service(admin::get_search_synonyms)
+            .service(admin::update_search_synonyms)
+            .service(admin::top_search_queries)
+            .service(admin::zero_result_search_queries)
+            .service(admin::search_click_through)
+    );
+}
@@ -0,0 +1,9 @@
+use actix_web::web;
+
+use crate::handlers::cohorts;
+
+pub fn cohorts_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(cohorts::create_cohort)
+       .service(cohorts::get_cohort_by_id)
+       .service(cohorts::get_course_cohorts);
+}
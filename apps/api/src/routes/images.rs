@@ -0,0 +1,10 @@
+use actix_web::web;
+
+use crate::handlers::images;
+
+pub fn images_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/images")
+            .service(images::get_image)
+    );
+}
@@ -0,0 +1,19 @@
+use actix_web::web;
+
+use crate::{handlers::{cohorts, notifications, progress}, middleware::auth_middleware::AuthMiddleware};
+
+pub fn me_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/me")
+            .wrap(AuthMiddleware::new())
+            .service(progress::enroll)
+            .service(progress::complete_lesson)
+            .service(progress::continue_learning)
+            .service(cohorts::join_cohort)
+            .service(cohorts::leave_cohort)
+            .service(cohorts::cohort_calendar)
+            .service(notifications::mark_all_notifications_read)
+            .service(notifications::mark_notification_read)
+            .service(notifications::list_notifications)
+    );
+}
@@ -0,0 +1,11 @@
+use actix_web::web;
+
+use crate::{handlers::media, middleware::auth_middleware::AuthMiddleware};
+
+pub fn media_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/media")
+            .wrap(AuthMiddleware::new())
+            .service(media::stream_lesson_video)
+    );
+}
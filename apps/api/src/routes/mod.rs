@@ -3,3 +3,10 @@ pub mod modules;
 pub mod lessons;
 pub mod search;
 pub mod auth;
+pub mod me;
+pub mod cohorts;
+pub mod uploads;
+pub mod media;
+pub mod storage;
+pub mod images;
+pub mod admin;
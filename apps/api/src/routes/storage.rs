@@ -0,0 +1,11 @@
+use actix_web::web;
+
+use crate::handlers::local_storage;
+
+pub fn storage_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/storage")
+            .service(local_storage::get_object)
+            .service(local_storage::put_object)
+    );
+}
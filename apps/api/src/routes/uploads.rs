@@ -0,0 +1,19 @@
+use actix_web::web;
+
+use crate::{handlers::{multipart_uploads, uploads}, middleware::auth_middleware::AuthMiddleware};
+
+pub fn uploads_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/uploads")
+            .wrap(AuthMiddleware::new())
+            .app_data(web::PayloadConfig::new(multipart_uploads::MAX_PART_PAYLOAD_BYTES))
+            .service(uploads::create_upload_intent)
+            .service(multipart_uploads::initiate_multipart_upload)
+            .service(multipart_uploads::get_multipart_upload)
+            .service(multipart_uploads::presign_multipart_part)
+            .service(multipart_uploads::upload_multipart_part)
+            .service(multipart_uploads::complete_multipart)
+            .service(multipart_uploads::abort_multipart)
+            .service(uploads::confirm_upload)
+    );
+}
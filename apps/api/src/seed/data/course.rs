@@ -1,3 +1,4 @@
+use api::models::image::cover_variants;
 use api::models::programe::{Course};
 use uuid::Uuid;
 
@@ -15,7 +16,7 @@ pub struct SeedLesson {
 pub struct SeedModule {
     pub title: &'static str,
     pub order: &'static str,
-    pub module_duration_minutes: &'static str,
+    pub module_duration_minutes: i32,
     pub lessons: Vec<SeedLesson>,
 }
 
@@ -33,7 +34,7 @@ pub struct SeedCourse {
     pub modules: Vec<SeedModule>,
     pub instructor: &'static str,
     pub featured: &'static bool,
-    pub total_duration_minutes: &'static str
+    pub total_duration_minutes: i32
 }
 
 pub fn get_seed_courses() -> Vec<SeedCourse> {
@@ -56,12 +57,12 @@ pub fn get_seed_courses() -> Vec<SeedCourse> {
                 "documents/rust-fundamentals/ownership/ownership-rules.pdf",
                 "documents/rust-fundamentals/ownership/borrowing-guide.pdf",
             ],
-            total_duration_minutes: "30",
+            total_duration_minutes: 30,
             modules: vec![
                 SeedModule {
                     title: "Introduction to Rust",
                     order: "1",
-                    module_duration_minutes: "25",
+                    module_duration_minutes: 25,
                     lessons: vec![
                         SeedLesson { 
                             title: "What is Rust?", 
@@ -84,7 +85,7 @@ pub fn get_seed_courses() -> Vec<SeedCourse> {
                 SeedModule {
                     title: "Ownership and Borrowing",
                     order: "2",
-                    module_duration_minutes: "45",
+                    module_duration_minutes: 45,
                     lessons: vec![
                         SeedLesson { 
                             title: "Ownership Basics", 
@@ -117,7 +118,7 @@ pub fn get_seed_courses() -> Vec<SeedCourse> {
             featured: &false,
             cover: "images/actix-web.jpeg",
             prerequisites: vec!["Rust Programming Fundamentals"],
-            total_duration_minutes: "40",
+            total_duration_minutes: 40,
             documents: vec![
                 "courses/actix-web/actix-documentation.pdf",
                 "courses/actix-web/rest-api-design.pdf",
@@ -128,7 +129,7 @@ pub fn get_seed_courses() -> Vec<SeedCourse> {
                 SeedModule {
                     title: "Actix Basics",
                     order: "1",
-                    module_duration_minutes: "35",
+                    module_duration_minutes: 35,
                     lessons: vec![
                         SeedLesson { 
                             title: "Setting up Actix", 
@@ -151,7 +152,7 @@ pub fn get_seed_courses() -> Vec<SeedCourse> {
                 SeedModule {
                     title: "Building APIs",
                     order: "2",
-                    module_duration_minutes: "45",
+                    module_duration_minutes: 45,
                     lessons: vec![
                         SeedLesson { 
                             title: "REST Endpoints", 
@@ -191,12 +192,12 @@ pub fn get_seed_courses() -> Vec<SeedCourse> {
                 "documents/async-rust/concurrency/task-management.pdf",
                 "documents/async-rust/concurrency/channel-patterns.pdf",
             ],
-            total_duration_minutes: "50",
+            total_duration_minutes: 50,
             modules: vec![
                 SeedModule {
                     title: "Async Basics",
                     order: "1",
-                    module_duration_minutes: "45",
+                    module_duration_minutes: 45,
                     lessons: vec![
                         SeedLesson { 
                             title: "Futures and async/await", 
@@ -219,7 +220,7 @@ pub fn get_seed_courses() -> Vec<SeedCourse> {
                 SeedModule {
                     title: "Concurrency Patterns",
                     order: "2",
-                    module_duration_minutes: "45",
+                    module_duration_minutes: 45,
                     lessons: vec![
                         SeedLesson { 
                             title: "Tasks and Spawning", 
@@ -252,7 +253,7 @@ pub fn get_seed_courses() -> Vec<SeedCourse> {
             featured: &true,
             cover: "images/postgresql.jpeg",
             prerequisites: vec![],
-            total_duration_minutes: "40",
+            total_duration_minutes: 40,
             documents: vec![
                 "courses/postgresql/postgres-guide.pdf",
                 "documents/postgresql/modeling/er-diagram-guide.pdf",
@@ -264,7 +265,7 @@ pub fn get_seed_courses() -> Vec<SeedCourse> {
                 SeedModule {
                     title: "Relational Modeling",
                     order: "1",
-                    module_duration_minutes: "35",
+                    module_duration_minutes: 35,
                     lessons: vec![
                         SeedLesson { 
                             title: "ER Diagrams", 
@@ -287,7 +288,7 @@ pub fn get_seed_courses() -> Vec<SeedCourse> {
                 SeedModule {
                     title: "PostgreSQL Optimization",
                     order: "2",
-                    module_duration_minutes: "45",
+                    module_duration_minutes: 45,
                     lessons: vec![
                         SeedLesson { 
                             title: "Indexes", 
@@ -327,12 +328,12 @@ pub fn get_seed_courses() -> Vec<SeedCourse> {
                 "documents/neo4j/advanced/pattern-examples.pdf",
                 "documents/neo4j/advanced/algorithm-guide.pdf",
             ],
-            total_duration_minutes: "90",
+            total_duration_minutes: 90,
             modules: vec![
                 SeedModule {
                     title: "Graph Theory Basics",
                     order: "1",
-                    module_duration_minutes: "35",
+                    module_duration_minutes: 35,
                     lessons: vec![
                         SeedLesson { 
                             title: "Nodes and Relationships", 
@@ -355,7 +356,7 @@ pub fn get_seed_courses() -> Vec<SeedCourse> {
                 SeedModule {
                     title: "Advanced Graph Queries",
                     order: "2",
-                    module_duration_minutes: "45",
+                    module_duration_minutes: 45,
                     lessons: vec![
                         SeedLesson { 
                             title: "Pattern Matching", 
@@ -395,12 +396,12 @@ pub fn get_seed_courses() -> Vec<SeedCourse> {
                 "documents/kafka/event-sourcing/patterns.pdf",
                 "documents/kafka/event-sourcing/integration-guide.pdf",
             ],
-            total_duration_minutes: "60",
+            total_duration_minutes: 60,
             modules: vec![
                 SeedModule {
                     title: "Kafka Fundamentals",
                     order: "1",
-                    module_duration_minutes: "35",
+                    module_duration_minutes: 35,
                     lessons: vec![
                         SeedLesson { 
                             title: "Topics and Partitions", 
@@ -423,7 +424,7 @@ pub fn get_seed_courses() -> Vec<SeedCourse> {
                 SeedModule {
                     title: "Event Sourcing",
                     order: "2",
-                    module_duration_minutes: "45",
+                    module_duration_minutes: 45,
                     lessons: vec![
                         SeedLesson { 
                             title: "Design Patterns", 
@@ -465,6 +466,7 @@ pub fn map_seed_to_course(sc: &SeedCourse) -> Course {
         instructor: sc.instructor.to_string(),
         featured: *sc.featured,
         cover: sc.cover.to_string(),
+        cover_variants: cover_variants(sc.cover),
         prerequisites: sc.prerequisites.iter().map(|s| s.to_string()).collect(),
         documents: sc.documents.iter().map(|s| s.to_string()).collect(),
         total_duration_minutes,
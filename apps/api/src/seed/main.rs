@@ -1,4 +1,3 @@
-use tokio;
 use api::config::Config;
 use crate::{seed_bucket::seed_bucket, seed_database::seed_database};
 
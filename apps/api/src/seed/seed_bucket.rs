@@ -1,7 +1,7 @@
 use anyhow::Result;
 use crate::data::course::get_seed_courses;
 use api::config::Config;
-use api::service::s3_service::S3Service;
+use api::service::build_storage;
 
 fn strip_prefix<'a>(path: &'a str, prefix: &str) -> &'a str {
     path.strip_prefix(prefix)
@@ -10,12 +10,12 @@ fn strip_prefix<'a>(path: &'a str, prefix: &str) -> &'a str {
 }
 
 pub async fn seed_bucket(config: &Config) -> Result<()> {
-    println!("Initializing S3 service...");
+    println!("Initializing storage...");
 
-    let s3_service = S3Service::new(config.s3_client.clone());
+    let (storage, _) = build_storage(config);
 
     for bucket in ["courses", "documents", "videos", "images"] {
-        let _ = s3_service.create_bucket(bucket).await;
+        storage.create_bucket(bucket).await?;
     }
 
     let courses = get_seed_courses();
@@ -35,7 +35,7 @@ pub async fn seed_bucket(config: &Config) -> Result<()> {
 
     println!("Uploading course documents...");
 
-    for course in &courses {
+    for (index, course) in courses.iter().enumerate() {
         for document_path in &course.documents {
             println!(" → Document: {}", document_path);
 
@@ -45,19 +45,17 @@ pub async fn seed_bucket(config: &Config) -> Result<()> {
                 ("documents", strip_prefix(document_path, "documents/"))
             };
 
-            let _ = s3_service
-                .upload_data(bucket, key, pdf_bytes.clone())
-                .await;
+            storage
+                .put(bucket, key, pdf_bytes.clone(), Some("application/pdf"))
+                .await?;
 
             println!("   ✔ Uploaded to {}/{}", bucket, key);
         }
-        println!("Uploading cover images...");
-        for image_bytes in &images{
-            let path = strip_prefix(&course.cover, "images/");
-            let _ = s3_service
-                .upload_data("images", path, image_bytes.clone())
-                .await;
-        }
+        println!("Uploading cover image...");
+        let path = strip_prefix(course.cover, "images/");
+        storage
+            .put("images", path, images[index % images.len()].clone(), Some("image/jpeg"))
+            .await?;
     }
 
 
@@ -71,9 +69,9 @@ pub async fn seed_bucket(config: &Config) -> Result<()> {
 
                 println!(" → Video: {}", raw_video_path);
 
-                let _ = s3_service
-                    .upload_data("videos", key, video_bytes.clone())
-                    .await;
+                storage
+                    .put("videos", key, video_bytes.clone(), Some("video/mp4"))
+                    .await?;
 
                 println!("   ✔ Uploaded to videos/{}", key);
             }
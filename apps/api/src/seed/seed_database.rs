@@ -14,7 +14,7 @@ pub async fn seed_database(config: &Config) -> Result<()> {
     let neo4j = Neo4jService::new(config.neo4j.clone());
     let kafka = KafkaService::new(config.kafka_producer.clone());
     let cache = CacheService::new(config.redis.clone());
-    let opensearch = OpenSearchService::new(config.opensearch.clone());
+    let opensearch = OpenSearchService::new(config.opensearch.clone(), config.synonyms_path.clone());
 
     println!("Clearing existing cache...");
     cache.delete_all("programs:*").await?;
@@ -33,9 +33,10 @@ pub async fn seed_database(config: &Config) -> Result<()> {
             .prop("status", course.status)
             .prop("category", course.category)
             .prop("level", course.level)
-            .prop("rating", course.rating.clone())
+            .prop("rating", *course.rating)
             .prop("instructor", course.instructor)
             .prop("featured", *course.featured)
+            .prop("prerequisites", course.prerequisites.clone())
             .prop("documents", course.documents.clone())
             .prop("total_duration_minutes", course.total_duration_minutes)
             .exec()
@@ -47,7 +48,7 @@ pub async fn seed_database(config: &Config) -> Result<()> {
 
        let _ = opensearch.index_course(&neo_course).await; 
 
-        for (_mod_index, module) in course.modules.iter().enumerate() {
+        for module in course.modules.iter() {
             println!("  Creating module: {}", module.title);
             let neo_module: Module = neo4j
                 .create_node("Module")
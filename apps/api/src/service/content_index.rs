@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use neo4rs::{Node, Row};
+
+use crate::error::ApiError;
+use crate::models::search::{ContentDocument, ContentKind, LessonHit, ModuleHit};
+use crate::service::neo4j_query::Neo4jQuery;
+use crate::service::AppServices;
+
+fn int_prop(node: &Node, key: &str) -> i64 {
+    node.get::<i64>(key)
+        .ok()
+        .or_else(|| node.get::<String>(key).ok().and_then(|v| v.parse().ok()))
+        .unwrap_or(0)
+}
+
+impl TryFrom<Row> for ContentDocument {
+    type Error = ApiError;
+
+    fn try_from(row: Row) -> Result<Self, Self::Error> {
+        let node: Node = row.get("n")
+            .map_err(|_| ApiError::Internal("Could not find key n".to_string()))?;
+        let kind = match row.get::<String>("kind").unwrap_or_default().as_str() {
+            "module" => ContentKind::Module,
+            _ => ContentKind::Lesson,
+        };
+        let duration_key = match kind {
+            ContentKind::Module => "module_duration_minutes",
+            ContentKind::Lesson => "duration_minutes",
+        };
+
+        Ok(Self {
+            id: node.get("id").map_err(|_| ApiError::Internal("missing content id".to_string()))?,
+            kind,
+            title: node.get("title").unwrap_or_default(),
+            order: int_prop(&node, "order"),
+            duration_minutes: int_prop(&node, duration_key),
+            paths: row.get("paths").unwrap_or_default(),
+        })
+    }
+}
+
+async fn fetch_lesson_document(services: &AppServices, lesson_id: &str) -> Result<Option<ContentDocument>, ApiError> {
+    let documents: Vec<ContentDocument> = services.neo4j
+        .query_nodes(
+            "MATCH (n:Lesson {id: $id})
+             OPTIONAL MATCH (m:Module)-[:HAS_LESSON]->(n)
+             OPTIONAL MATCH (c:Course)-[:HAS_MODULE]->(m)
+             WITH n, collect(DISTINCT {
+                 course_id: c.id, course_title: coalesce(c.title, ''),
+                 module_id: m.id, module_title: coalesce(m.title, '')
+             }) AS paths
+             RETURN n, 'lesson' AS kind, [p IN paths WHERE p.course_id IS NOT NULL] AS paths"
+        )
+        .param("id", lesson_id.to_string())
+        .fetch_all()
+        .await?;
+
+    Ok(documents.into_iter().next())
+}
+
+async fn fetch_module_document(services: &AppServices, module_id: &str) -> Result<Option<ContentDocument>, ApiError> {
+    let documents: Vec<ContentDocument> = services.neo4j
+        .query_nodes(
+            "MATCH (n:Module {id: $id})
+             OPTIONAL MATCH (c:Course)-[:HAS_MODULE]->(n)
+             WITH n, collect(DISTINCT {course_id: c.id, course_title: coalesce(c.title, '')}) AS paths
+             RETURN n, 'module' AS kind, [p IN paths WHERE p.course_id IS NOT NULL] AS paths"
+        )
+        .param("id", module_id.to_string())
+        .fetch_all()
+        .await?;
+
+    Ok(documents.into_iter().next())
+}
+
+async fn fetch_ids(query: Neo4jQuery<'_>) -> Result<Vec<String>, ApiError> {
+    let rows: Vec<RelatedId> = query.fetch_all().await?;
+    Ok(rows.into_iter().map(|r| r.0).collect())
+}
+
+async fn related_ids(services: &AppServices, cypher: &str, id: &str) -> Result<Vec<String>, ApiError> {
+    fetch_ids(services.neo4j.query_nodes(cypher).param("id", id.to_string())).await
+}
+
+struct RelatedId(String);
+
+impl TryFrom<Row> for RelatedId {
+    type Error = ApiError;
+
+    fn try_from(row: Row) -> Result<Self, Self::Error> {
+        row.get("id")
+            .map(RelatedId)
+            .map_err(|_| ApiError::Internal("Could not find key id".to_string()))
+    }
+}
+
+pub async fn module_lesson_ids(services: &AppServices, module_id: &str) -> Result<Vec<String>, ApiError> {
+    related_ids(services, "MATCH (:Module {id: $id})-[:HAS_LESSON]->(l:Lesson) RETURN DISTINCT l.id AS id", module_id).await
+}
+
+pub async fn course_module_ids(services: &AppServices, course_id: &str) -> Result<Vec<String>, ApiError> {
+    related_ids(services, "MATCH (:Course {id: $id})-[:HAS_MODULE]->(m:Module) RETURN DISTINCT m.id AS id", course_id).await
+}
+
+pub async fn lesson_module_ids(services: &AppServices, lesson_id: &str) -> Result<Vec<String>, ApiError> {
+    related_ids(services, "MATCH (m:Module)-[:HAS_LESSON]->(:Lesson {id: $id}) RETURN DISTINCT m.id AS id", lesson_id).await
+}
+
+pub async fn sync_lesson(services: &AppServices, lesson_id: &str) -> Result<(), ApiError> {
+    match fetch_lesson_document(services, lesson_id).await? {
+        Some(document) => services.opensearch.index_content(&document).await,
+        None => services.opensearch.delete_content(lesson_id).await,
+    }
+}
+
+pub async fn sync_lessons(services: &AppServices, lesson_ids: &[String]) -> Result<(), ApiError> {
+    for lesson_id in lesson_ids {
+        sync_lesson(services, lesson_id).await?;
+    }
+    Ok(())
+}
+
+/// Re-indexes a module and its lessons, whose paths include the module's title.
+pub async fn sync_module(services: &AppServices, module_id: &str) -> Result<(), ApiError> {
+    match fetch_module_document(services, module_id).await? {
+        Some(document) => services.opensearch.index_content(&document).await?,
+        None => services.opensearch.delete_content(module_id).await?,
+    }
+
+    sync_lessons(services, &module_lesson_ids(services, module_id).await?).await
+}
+
+/// Rebuilds the module and lesson index from Neo4j, e.g. after the index was created.
+pub async fn sync_all_content(services: &AppServices) -> Result<usize, ApiError> {
+    let module_ids = fetch_ids(services.neo4j.query_nodes("MATCH (m:Module) RETURN m.id AS id")).await?;
+    let lesson_ids = fetch_ids(services.neo4j.query_nodes("MATCH (l:Lesson) RETURN l.id AS id")).await?;
+
+    for module_id in &module_ids {
+        if let Some(document) = fetch_module_document(services, module_id).await? {
+            services.opensearch.index_content(&document).await?;
+        }
+    }
+    sync_lessons(services, &lesson_ids).await?;
+
+    Ok(module_ids.len() + lesson_ids.len())
+}
+
+/// Groups module and lesson hits under their courses, keeping the order hits were scored in.
+pub fn group_content_hits(hits: Vec<ContentDocument>) -> HashMap<String, Vec<ModuleHit>> {
+    let mut grouped: HashMap<String, Vec<ModuleHit>> = HashMap::new();
+
+    for hit in hits {
+        for path in &hit.paths {
+            let modules = grouped.entry(path.course_id.clone()).or_default();
+
+            let (module_id, module_title) = match hit.kind {
+                ContentKind::Module => (hit.id.clone(), hit.title.clone()),
+                ContentKind::Lesson => match (&path.module_id, &path.module_title) {
+                    (Some(id), title) => (id.clone(), title.clone().unwrap_or_default()),
+                    (None, _) => continue,
+                },
+            };
+
+            let index = match modules.iter().position(|m| m.id == module_id) {
+                Some(index) => index,
+                None => {
+                    modules.push(ModuleHit {
+                        id: module_id,
+                        title: module_title,
+                        matched: false,
+                        lessons: vec![],
+                    });
+                    modules.len() - 1
+                }
+            };
+
+            let module = &mut modules[index];
+            match hit.kind {
+                ContentKind::Module => module.matched = true,
+                ContentKind::Lesson => {
+                    if !module.lessons.iter().any(|l| l.id == hit.id) {
+                        module.lessons.push(LessonHit {
+                            id: hit.id.clone(),
+                            title: hit.title.clone(),
+                            duration_minutes: hit.duration_minutes,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    grouped
+}
@@ -0,0 +1,70 @@
+use crate::error::ApiError;
+use crate::models::programe::Course;
+use crate::service::media_gc::resolve;
+use crate::service::AppServices;
+
+pub struct ExtractedDocument {
+    pub reference: String,
+    pub title: String,
+    pub pages: Vec<String>,
+}
+
+/// `documents/rust/ownership/ownership-rules.pdf` becomes `Ownership Rules`.
+pub fn document_title(reference: &str) -> String {
+    let file = reference.rsplit('/').next().unwrap_or(reference);
+    let stem = file.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(file);
+
+    stem.split(|c: char| c == '-' || c == '_' || c.is_whitespace())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub async fn extract_pages(bytes: Vec<u8>) -> Result<Vec<String>, ApiError> {
+    tokio::task::spawn_blocking(move || pdf_extract::extract_text_from_mem_by_pages(&bytes))
+        .await
+        .map_err(|e| ApiError::Internal(format!("PDF worker failed: {}", e)))?
+        .map_err(|e| ApiError::BadRequest(format!("Unreadable PDF: {}", e)))
+}
+
+async fn extract_document(services: &AppServices, reference: &str) -> Result<ExtractedDocument, ApiError> {
+    let (bucket, key) = resolve(reference, "documents");
+    let bytes = services.storage.download(&bucket, &key).await?;
+
+    let pages = extract_pages(bytes)
+        .await?
+        .into_iter()
+        .map(|page| page.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect();
+
+    Ok(ExtractedDocument {
+        reference: reference.to_string(),
+        title: document_title(reference),
+        pages,
+    })
+}
+
+/// Replaces every indexed page for the course with freshly extracted text.
+/// Documents that cannot be read are skipped so one bad PDF does not hide the rest.
+pub async fn index_course_documents(services: &AppServices, course: &Course) -> Result<usize, ApiError> {
+    services.opensearch.delete_course_documents(&course.id).await?;
+
+    let mut indexed = 0;
+    for reference in &course.documents {
+        match extract_document(services, reference).await {
+            Ok(document) => {
+                indexed += services.opensearch.index_document_pages(&course.id, &document).await?;
+            }
+            Err(e) => eprintln!("⚠ Skipping document {} of course {}: {}", reference, course.id, e),
+        }
+    }
+
+    Ok(indexed)
+}
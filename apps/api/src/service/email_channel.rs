@@ -0,0 +1,42 @@
+use async_trait::async_trait;
+use lettre::{message::header::ContentType, AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use crate::{config::SmtpConfig, error::ApiError, models::{auth::User, notification::Notification}, traits::notification_channel::NotificationChannel};
+
+pub struct EmailChannel {
+    mailer: AsyncSmtpTransport<Tokio1Executor>,
+    from: String,
+}
+
+impl EmailChannel {
+    pub fn new(config: SmtpConfig) -> Self {
+        let mailer = AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(config.host)
+            .port(config.port)
+            .build();
+
+        Self { mailer, from: config.from }
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for EmailChannel {
+    fn name(&self) -> &'static str {
+        "email"
+    }
+
+    async fn deliver(&self, user: &User, notification: &Notification) -> Result<(), ApiError> {
+        let message = Message::builder()
+            .from(self.from.parse().map_err(|e| ApiError::Internal(format!("Invalid sender address: {}", e)))?)
+            .to(user.email.parse().map_err(|e| ApiError::Internal(format!("Invalid recipient address: {}", e)))?)
+            .subject(&notification.title)
+            .header(ContentType::TEXT_PLAIN)
+            .body(notification.body.clone())
+            .map_err(|e| ApiError::Internal(format!("Failed to build email: {}", e)))?;
+
+        self.mailer
+            .send(message)
+            .await
+            .map_err(|e| ApiError::Internal(format!("SMTP error: {}", e)))?;
+
+        Ok(())
+    }
+}
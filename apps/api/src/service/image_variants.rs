@@ -0,0 +1,113 @@
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+
+use crate::error::ApiError;
+use crate::models::image::{variant_key, ImageFormat, COVER_BUCKET, VARIANT_WIDTHS};
+use crate::traits::object_storage::{ObjectHead, ObjectStorage};
+
+const JPEG_QUALITY: u8 = 82;
+
+/// Resizes an encoded image to `width` (never upscaling) and re-encodes it.
+pub fn render_variant(original: &[u8], width: u32, format: ImageFormat) -> Result<Vec<u8>, ApiError> {
+    let decoded = image::load_from_memory(original)
+        .map_err(|e| ApiError::BadRequest(format!("Unreadable image: {}", e)))?;
+
+    let resized = if decoded.width() > width {
+        let height = (decoded.height() as u64 * width as u64 / decoded.width() as u64).max(1) as u32;
+        decoded.resize_exact(width, height, FilterType::Lanczos3)
+    } else {
+        decoded
+    };
+
+    let mut out = Vec::new();
+    let result = match format {
+        ImageFormat::Jpeg => resized
+            .to_rgb8()
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut out, JPEG_QUALITY)),
+        ImageFormat::Webp => resized
+            .to_rgba8()
+            .write_with_encoder(WebPEncoder::new_lossless(&mut out)),
+    };
+    result.map_err(|e| ApiError::Internal(format!("Failed to encode image: {}", e)))?;
+
+    Ok(out)
+}
+
+/// Identifies the current content of an original, from its ETag or else its modification time.
+pub fn source_version(head: &ObjectHead) -> String {
+    let version: String = head
+        .e_tag
+        .as_deref()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .collect();
+
+    if !version.is_empty() {
+        return version;
+    }
+    head.last_modified
+        .map(|m| m.timestamp().to_string())
+        .unwrap_or_else(|| "0".to_string())
+}
+
+pub async fn original_version(storage: &dyn ObjectStorage, original_key: &str) -> Result<String, ApiError> {
+    storage
+        .head(COVER_BUCKET, original_key)
+        .await?
+        .map(|head| source_version(&head))
+        .ok_or_else(|| ApiError::NotFound("Image not found".to_string()))
+}
+
+async fn render_and_store(
+    storage: &dyn ObjectStorage,
+    original: Vec<u8>,
+    key: &str,
+    width: u32,
+    format: ImageFormat,
+) -> Result<(), ApiError> {
+    let bytes = tokio::task::spawn_blocking(move || render_variant(&original, width, format))
+        .await
+        .map_err(|e| ApiError::Internal(format!("Image worker failed: {}", e)))??;
+
+    storage
+        .put(COVER_BUCKET, key, bytes, Some(format.content_type()))
+        .await
+}
+
+/// Returns the key of the variant for `version` of the original, generating and storing it
+/// first when it does not exist yet.
+pub async fn ensure_variant(
+    storage: &dyn ObjectStorage,
+    original_key: &str,
+    version: &str,
+    width: u32,
+    format: ImageFormat,
+) -> Result<String, ApiError> {
+    let key = variant_key(original_key, version, width, format);
+
+    if !storage.object_exists(COVER_BUCKET, &key).await? {
+        let original = storage.download(COVER_BUCKET, original_key).await?;
+        render_and_store(storage, original, &key, width, format).await?;
+    }
+
+    Ok(key)
+}
+
+/// Generates every configured variant for a cover, downloading the original once.
+pub async fn generate_cover_variants(storage: &dyn ObjectStorage, original_key: &str) -> Result<Vec<String>, ApiError> {
+    let version = original_version(storage, original_key).await?;
+    let original = storage.download(COVER_BUCKET, original_key).await?;
+    let mut keys = Vec::new();
+
+    for width in VARIANT_WIDTHS {
+        for format in ImageFormat::ALL {
+            let key = variant_key(original_key, &version, width, format);
+            render_and_store(storage, original.clone(), &key, width, format).await?;
+            keys.push(key);
+        }
+    }
+
+    Ok(keys)
+}
@@ -0,0 +1,214 @@
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::error::ApiError;
+
+/// Settings and mappings of every search index. Changing a programs mapping here, or the
+/// synonyms it is built with, changes its checksum, which is how the app notices the live
+/// index needs a reindex.
+pub fn programs_index_body(synonyms: &[String]) -> Value {
+    let mut filters = json!({
+        "english_stemmer": { "type": "stemmer", "language": "english" },
+        "english_possessive": { "type": "stemmer", "language": "possessive_english" }
+    });
+
+    // Synonyms only run at search time, so indexed tokens never depend on them. They are
+    // still part of the index settings, which an open index cannot change, so editing them
+    // means building a new index and swapping the alias.
+    let mut search_chain = vec!["english_possessive", "lowercase", "asciifolding"];
+    if !synonyms.is_empty() {
+        filters["catalog_synonyms"] = json!({
+            "type": "synonym_graph",
+            "synonyms": synonyms,
+            "lenient": true
+        });
+        search_chain.push("catalog_synonyms");
+    }
+    search_chain.push("english_stemmer");
+
+    with_checksum(json!({
+        "settings": {
+            "number_of_shards": 1,
+            "number_of_replicas": 0,
+            "analysis": {
+                "filter": filters,
+                "analyzer": {
+                    "default": {
+                        "type": "custom",
+                        "tokenizer": "standard",
+                        "filter": ["lowercase", "asciifolding"]
+                    },
+                    "english_folded": {
+                        "type": "custom",
+                        "tokenizer": "standard",
+                        "filter": ["english_possessive", "lowercase", "asciifolding", "english_stemmer"]
+                    },
+                    "english_search": {
+                        "type": "custom",
+                        "tokenizer": "standard",
+                        "filter": search_chain
+                    }
+                }
+            }
+        },
+        "mappings": {
+            "properties": {
+                "id": { "type": "keyword" },
+                "title": {
+                    "type": "text",
+                    "analyzer": "english_folded",
+                    "search_analyzer": "english_search",
+                    "fields": {
+                        "keyword": { "type": "keyword" },
+                        "suggest": { "type": "completion" }
+                    }
+                },
+                "description": {
+                    "type": "text",
+                    "analyzer": "english_folded",
+                    "search_analyzer": "english_search"
+                },
+                "status": { "type": "keyword" },
+                "category": {
+                    "type": "keyword",
+                    "fields": {
+                        "suggest": { "type": "completion" }
+                    }
+                },
+                "level": { "type": "keyword" },
+                "rating": { "type": "float" },
+                "instructor": {
+                    "type": "keyword",
+                    "fields": {
+                        "suggest": { "type": "completion" }
+                    }
+                },
+                "featured": { "type": "boolean" },
+                "cover": { "type": "keyword" },
+                "cover_variants": { "type": "object", "enabled": false },
+                "prerequisites": { "type": "keyword" },
+                "documents": { "type": "keyword" },
+                "total_duration_minutes": { "type": "integer" },
+            }
+        }
+    }))
+}
+
+pub fn documents_index_body() -> Value {
+    json!({
+        "settings": {
+            "number_of_shards": 1,
+            "number_of_replicas": 0
+        },
+        "mappings": {
+            "properties": {
+                "course_id": { "type": "keyword" },
+                "document": { "type": "keyword" },
+                "document_title": { "type": "text" },
+                "page": { "type": "integer" },
+                "content": { "type": "text" }
+            }
+        }
+    })
+}
+
+pub fn content_index_body() -> Value {
+    json!({
+        "settings": {
+            "number_of_shards": 1,
+            "number_of_replicas": 0
+        },
+        "mappings": {
+            "properties": {
+                "id": { "type": "keyword" },
+                "kind": { "type": "keyword" },
+                "title": {
+                    "type": "text",
+                    "fields": {
+                        "keyword": { "type": "keyword" },
+                        "suggest": {
+                            "type": "completion",
+                            "contexts": [
+                                { "name": "kind", "type": "category", "path": "kind" }
+                            ]
+                        }
+                    }
+                },
+                "order": { "type": "integer" },
+                "duration_minutes": { "type": "integer" },
+                "paths": {
+                    "properties": {
+                        "course_id": { "type": "keyword" },
+                        "course_title": { "type": "text" },
+                        "module_id": { "type": "keyword" },
+                        "module_title": { "type": "text" }
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// The checksum stamped into `mappings._meta` by `programs_index_body`.
+pub fn index_checksum(body: &Value) -> String {
+    body["mappings"]["_meta"]["checksum"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Checks one line in the Solr synonym format: `postgres, postgresql` or `async => asynchronous`.
+pub fn validate_synonym(line: &str) -> Result<(), String> {
+    let terms_ok = |side: &str| side.split(',').all(|term| !term.trim().is_empty());
+
+    let valid = match line.split_once("=>") {
+        Some((from, to)) => !to.contains("=>") && terms_ok(from) && terms_ok(to),
+        None => line.contains(',') && terms_ok(line),
+    };
+
+    if valid && !line.contains('\n') {
+        Ok(())
+    } else {
+        Err(format!("'{}' is not a synonym rule like 'a, b' or 'a => b'", line))
+    }
+}
+
+/// Reads the synonyms file, one rule per line; blank lines and `#` comments are skipped.
+/// A missing file simply means no synonyms.
+pub async fn load_synonyms(path: &str) -> Result<Vec<String>, ApiError> {
+    let contents = match tokio::fs::read_to_string(path).await {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(ApiError::Internal(format!("Failed to read synonyms from {}: {}", path, e))),
+    };
+
+    let synonyms: Vec<String> = contents
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.to_string())
+        .collect();
+
+    synonyms
+        .iter()
+        .try_for_each(|line| validate_synonym(line))
+        .map_err(|e| ApiError::Internal(format!("Invalid synonyms file {}: {}", path, e)))?;
+
+    Ok(synonyms)
+}
+
+pub async fn save_synonyms(path: &str, synonyms: &[String]) -> Result<(), ApiError> {
+    let mut contents = synonyms.join("\n");
+    contents.push('\n');
+
+    tokio::fs::write(path, contents)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to write synonyms to {}: {}", path, e)))
+}
+
+fn with_checksum(mut body: Value) -> Value {
+    // The body is a literal, so its serialized form only changes when the definition does.
+    let digest = Sha256::digest(body.to_string().as_bytes());
+    body["mappings"]["_meta"] = json!({ "checksum": hex::encode(&digest[..8]) });
+    body
+}
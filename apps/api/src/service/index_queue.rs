@@ -0,0 +1,299 @@
+use crate::error::ApiError;
+use crate::models::index_retry::{IndexRetry, IndexTarget};
+use crate::models::programe::Course;
+use crate::service::opensearch::OpenSearchService;
+use crate::service::postgres_service::PostgresService;
+use crate::service::{content_index, AppServices};
+
+const BASE_BACKOFF_SECONDS: i64 = 30;
+const MAX_BACKOFF_SECONDS: i64 = 60 * 60;
+
+/// Brings the search index in line with what Neo4j holds now, indexing the node
+/// or removing it when it no longer exists. Retries always run this, so a
+/// queued entry never writes stale data over a newer change.
+pub async fn sync(services: &AppServices, target: IndexTarget, id: &str) -> Result<(), ApiError> {
+    match target {
+        IndexTarget::Course => {
+            let courses: Vec<Course> = services.neo4j
+                .query_nodes("MATCH (c:Course {id: $id}) RETURN c")
+                .param("id", id.to_string())
+                .fetch_key("c")
+                .fetch()
+                .await?;
+
+            match courses.first() {
+                Some(course) => services.opensearch.index_course(course).await,
+                None => {
+                    services.opensearch.delete_course(id).await?;
+                    services.opensearch.delete_course_documents(id).await
+                }
+            }
+        }
+        IndexTarget::Module => content_index::sync_module(services, id).await,
+        IndexTarget::Lesson => content_index::sync_lesson(services, id).await,
+    }
+}
+
+/// Records a failed index write; the search index worker picks it up on its next pass.
+pub async fn enqueue(postgres: &PostgresService, target: IndexTarget, id: &str, error: &ApiError) {
+    eprintln!("⚠ Queued {} {} for reindexing: {}", target.as_str(), id, error);
+
+    let result = postgres
+        .query(
+            r#"
+            INSERT INTO search_index_retries (target, target_id, last_error)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (target, target_id) DO UPDATE SET last_error = EXCLUDED.last_error
+            RETURNING *
+            "#,
+        )
+        .bind(target.as_str())
+        .bind(id)
+        .bind(error.to_string())
+        .fetch_one::<IndexRetry>()
+        .await;
+
+    if let Err(e) = result {
+        eprintln!("⚠ Failed to queue {} {} for reindexing: {}", target.as_str(), id, e);
+    }
+}
+
+/// Indexes a course that was just written, queueing it when OpenSearch is unavailable
+/// so the write itself still succeeds.
+pub async fn index_course(services: &AppServices, course: &Course) {
+    index_course_or_queue(&services.opensearch, &services.postgress, course).await
+}
+
+async fn index_course_or_queue(opensearch: &OpenSearchService, postgres: &PostgresService, course: &Course) {
+    if let Err(e) = opensearch.index_course(course).await {
+        enqueue(postgres, IndexTarget::Course, &course.id, &e).await;
+    }
+}
+
+/// Removes a deleted course and its document pages, queueing the removal on failure.
+pub async fn remove_course(services: &AppServices, course_id: &str) {
+    remove_course_or_queue(&services.opensearch, &services.postgress, course_id).await
+}
+
+async fn remove_course_or_queue(opensearch: &OpenSearchService, postgres: &PostgresService, course_id: &str) {
+    let result = match opensearch.delete_course(course_id).await {
+        Ok(()) => opensearch.delete_course_documents(course_id).await,
+        Err(e) => Err(e),
+    };
+
+    if let Err(e) = result {
+        enqueue(postgres, IndexTarget::Course, course_id, &e).await;
+    }
+}
+
+/// Syncs a module or lesson after a write, queueing it when OpenSearch is unavailable.
+pub async fn sync_or_queue(services: &AppServices, target: IndexTarget, id: &str) {
+    if let Err(e) = sync(services, target, id).await {
+        enqueue(&services.postgress, target, id, &e).await;
+    }
+}
+
+pub async fn sync_all(services: &AppServices, target: IndexTarget, ids: &[String]) {
+    for id in ids {
+        sync_or_queue(services, target, id).await;
+    }
+}
+
+pub async fn pending(services: &AppServices) -> Result<Vec<IndexRetry>, ApiError> {
+    services
+        .postgress
+        .query("SELECT * FROM search_index_retries ORDER BY next_attempt_at, created_at")
+        .fetch_all()
+        .await
+}
+
+/// Retries every entry that is due. Successes leave the queue; failures back off
+/// exponentially up to an hour and are retried until OpenSearch accepts them.
+pub async fn retry_due(services: &AppServices, limit: i64) -> Result<usize, ApiError> {
+    let due: Vec<IndexRetry> = services
+        .postgress
+        .query(
+            r#"
+            SELECT * FROM search_index_retries
+            WHERE next_attempt_at <= CURRENT_TIMESTAMP
+            ORDER BY next_attempt_at
+            LIMIT $1::bigint
+            "#,
+        )
+        .bind(limit)
+        .fetch_all()
+        .await?;
+
+    let mut synced = 0;
+    for entry in due {
+        let Some(target) = IndexTarget::parse(&entry.target) else {
+            eprintln!("⚠ Dropping reindex entry with unknown target {}", entry.target);
+            remove_entry(services, &entry).await?;
+            continue;
+        };
+
+        match sync(services, target, &entry.target_id).await {
+            Ok(()) => {
+                remove_entry(services, &entry).await?;
+                synced += 1;
+            }
+            Err(e) => {
+                let backoff = (BASE_BACKOFF_SECONDS << entry.attempts.clamp(0, 10)).min(MAX_BACKOFF_SECONDS);
+                let _: Option<IndexRetry> = services
+                    .postgress
+                    .query(
+                        r#"
+                        UPDATE search_index_retries
+                        SET attempts = attempts + 1,
+                            last_error = $3,
+                            next_attempt_at = CURRENT_TIMESTAMP + ($4::bigint * INTERVAL '1 second')
+                        WHERE target = $1 AND target_id = $2
+                        RETURNING *
+                        "#,
+                    )
+                    .bind(&entry.target)
+                    .bind(&entry.target_id)
+                    .bind(e.to_string())
+                    .bind(backoff)
+                    .fetch_optional()
+                    .await?;
+            }
+        }
+    }
+
+    Ok(synced)
+}
+
+async fn remove_entry(services: &AppServices, entry: &IndexRetry) -> Result<(), ApiError> {
+    let _: Option<IndexRetry> = services
+        .postgress
+        .query("DELETE FROM search_index_retries WHERE target = $1 AND target_id = $2 RETURNING *")
+        .bind(&entry.target)
+        .bind(&entry.target_id)
+        .fetch_optional()
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use opensearch::http::transport::{SingleNodeConnectionPool, TransportBuilder};
+    use opensearch::http::Url;
+    use opensearch::OpenSearch;
+    use sqlx::PgPool;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::sync::OnceCell;
+
+    use super::*;
+
+    /// An OpenSearch stand-in answering each request line with the status `respond` picks.
+    async fn stub_opensearch(respond: fn(&str) -> &'static str) -> OpenSearchService {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = vec![0u8; 64 * 1024];
+                let n = socket.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]);
+                let status = respond(request.lines().next().unwrap_or_default());
+                let body = r#"{}"#;
+                let response = format!(
+                    "HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+
+        let transport = TransportBuilder::new(SingleNodeConnectionPool::new(url))
+            .disable_proxy()
+            .build()
+            .unwrap();
+        OpenSearchService::new(Arc::new(OpenSearch::new(transport)), String::new())
+    }
+
+    static SCHEMA: OnceCell<()> = OnceCell::const_new();
+
+    /// Runs against the database in `TEST_POSTGRES_URL`, creating the schema from
+    /// `setup/init.sql`; skipped when it is not set.
+    async fn test_postgres() -> Option<PostgresService> {
+        let Ok(url) = std::env::var("TEST_POSTGRES_URL") else {
+            eprintln!("TEST_POSTGRES_URL not set, skipping");
+            return None;
+        };
+        let pool = PgPool::connect(&url).await.unwrap();
+        SCHEMA
+            .get_or_init(|| async {
+                sqlx::raw_sql(include_str!("../../setup/init.sql")).execute(&pool).await.unwrap();
+            })
+            .await;
+        Some(PostgresService::new(pool))
+    }
+
+    async fn queued(postgres: &PostgresService, id: &str) -> Option<IndexRetry> {
+        postgres
+            .query("SELECT * FROM search_index_retries WHERE target = 'course' AND target_id = $1")
+            .bind(id)
+            .fetch_optional()
+            .await
+            .unwrap()
+    }
+
+    fn course(id: &str) -> Course {
+        Course {
+            id: id.to_string(),
+            title: "Rust".to_string(),
+            description: String::new(),
+            status: "active".to_string(),
+            category: "Rust".to_string(),
+            level: "beginner".to_string(),
+            rating: 4.5,
+            instructor: String::new(),
+            featured: false,
+            cover: String::new(),
+            cover_variants: Vec::new(),
+            prerequisites: Vec::new(),
+            documents: Vec::new(),
+            total_duration_minutes: 30,
+        }
+    }
+
+    #[tokio::test]
+    async fn rejected_index_writes_are_queued() {
+        let Some(postgres) = test_postgres().await else { return };
+        let opensearch = stub_opensearch(|line| if line.starts_with("HEAD ") { "200 OK" } else { "503 Service Unavailable" }).await;
+        let id = uuid::Uuid::new_v4().to_string();
+
+        index_course_or_queue(&opensearch, &postgres, &course(&id)).await;
+
+        let entry = queued(&postgres, &id).await.expect("index failure was not queued");
+        assert!(entry.last_error.contains("503"), "{}", entry.last_error);
+    }
+
+    #[tokio::test]
+    async fn rejected_deletes_are_queued() {
+        let Some(postgres) = test_postgres().await else { return };
+        let opensearch = stub_opensearch(|line| if line.starts_with("HEAD ") { "200 OK" } else { "429 Too Many Requests" }).await;
+        let id = uuid::Uuid::new_v4().to_string();
+
+        remove_course_or_queue(&opensearch, &postgres, &id).await;
+
+        assert!(queued(&postgres, &id).await.is_some(), "delete failure was not queued");
+    }
+
+    #[tokio::test]
+    async fn deleting_a_missing_course_is_not_queued() {
+        let Some(postgres) = test_postgres().await else { return };
+        let opensearch = stub_opensearch(|line| if line.starts_with("DELETE ") { "404 Not Found" } else { "200 OK" }).await;
+        let id = uuid::Uuid::new_v4().to_string();
+
+        remove_course_or_queue(&opensearch, &postgres, &id).await;
+
+        assert!(queued(&postgres, &id).await.is_none());
+    }
+}
@@ -1,7 +1,7 @@
 use rdkafka::producer::{FutureProducer, FutureRecord};
 use std::{sync::Arc, time::Duration};
 
-use crate::{dtos::events::CacheInvalidationEvent, error::ApiError};
+use crate::{dtos::events::{CacheInvalidationEvent, LessonCompletedEvent}, error::ApiError};
 
 pub struct KafkaService {
    producer: Arc<FutureProducer>,
@@ -34,5 +34,29 @@ impl KafkaService {
 
         Ok(())
     }
+
+    /// Learner progress goes to its own topic, keyed by user so one learner's events stay ordered.
+    pub async fn publish_lesson_completed(&self, user_id: &str, course_id: &str, lesson_id: &str) -> Result<(), ApiError> {
+        let event = LessonCompletedEvent {
+            user_id: user_id.to_string(),
+            course_id: course_id.to_string(),
+            lesson_id: lesson_id.to_string(),
+            timestamp: chrono::Utc::now().timestamp(),
+        };
+
+        let payload = serde_json::to_string(&event)
+            .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+        let record = FutureRecord::to("learner-progress")
+            .payload(&payload)
+            .key(user_id);
+
+        self.producer
+            .send(record, Duration::from_secs(15))
+            .await
+            .map_err(|(e, _)| ApiError::Internal(format!("Kafka error: {:?}", e)))?;
+
+        Ok(())
+    }
 }
 
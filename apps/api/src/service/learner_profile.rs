@@ -0,0 +1,232 @@
+use std::collections::{HashMap, HashSet};
+use neo4rs::Row;
+use sqlx::types::Uuid;
+
+use crate::error::ApiError;
+use crate::models::programe::Course;
+use crate::models::progress::{CourseLessonCompletions, Enrollment};
+use crate::models::search::Personalization;
+use crate::service::AppServices;
+
+const MAX_INTEREST_CATEGORIES: usize = 5;
+const LEVELS: [&str; 3] = ["beginner", "intermediate", "advanced"];
+
+struct LessonCount {
+    id: String,
+    lessons: i64,
+}
+
+impl TryFrom<Row> for LessonCount {
+    type Error = ApiError;
+
+    fn try_from(row: Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.get("id").map_err(|_| ApiError::Internal("Could not find key id".to_string()))?,
+            lessons: row.get("lessons").unwrap_or(0),
+        })
+    }
+}
+
+struct CourseId(String);
+
+impl TryFrom<Row> for CourseId {
+    type Error = ApiError;
+
+    fn try_from(row: Row) -> Result<Self, Self::Error> {
+        row.get("id")
+            .map(CourseId)
+            .map_err(|_| ApiError::Internal("Could not find key id".to_string()))
+    }
+}
+
+/// Lessons currently in each course, for deciding whether a learner has finished it.
+pub async fn lesson_counts(services: &AppServices, course_ids: Vec<String>) -> Result<HashMap<String, i64>, ApiError> {
+    Ok(services.neo4j
+        .query_nodes(
+            "MATCH (c:Course)-[:HAS_MODULE]->(:Module)-[:HAS_LESSON]->(l:Lesson)
+             WHERE c.id IN $ids
+             RETURN c.id AS id, count(DISTINCT l) AS lessons"
+        )
+        .param("ids", course_ids)
+        .fetch_all::<LessonCount>()
+        .await?
+        .into_iter()
+        .map(|c| (c.id, c.lessons))
+        .collect())
+}
+
+/// A course is finished once every lesson it currently has is completed.
+pub fn is_finished(completion: &CourseLessonCompletions, lesson_counts: &HashMap<String, i64>) -> bool {
+    lesson_counts
+        .get(&completion.course_id)
+        .map(|total| *total > 0 && completion.completed_lessons >= *total)
+        .unwrap_or(false)
+}
+
+fn level_rank(level: &str) -> usize {
+    LEVELS.iter().position(|l| *l == level).unwrap_or(0)
+}
+
+fn counts<'a>(values: impl IntoIterator<Item = &'a str>) -> HashMap<&'a str, usize> {
+    let mut counts = HashMap::new();
+    for value in values.into_iter().filter(|v| !v.is_empty()) {
+        *counts.entry(value).or_default() += 1;
+    }
+    counts
+}
+
+/// The level of the most studied courses, the higher one on a tie and then the first
+/// alphabetically, so the result never depends on map order.
+fn dominant_level<'a>(levels: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let levels: Vec<String> = levels.into_iter().map(|l| l.to_lowercase()).collect();
+
+    counts(levels.iter().map(|l| l.as_str()))
+        .into_iter()
+        .max_by(|a, b| {
+            (a.1, level_rank(a.0))
+                .cmp(&(b.1, level_rank(b.0)))
+                .then_with(|| b.0.cmp(a.0))
+        })
+        .map(|(level, _)| level.to_string())
+}
+
+/// The most studied categories, alphabetical on a tie, at most `MAX_INTEREST_CATEGORIES`.
+fn top_categories<'a>(categories: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut categories: Vec<(&str, usize)> = counts(categories).into_iter().collect();
+    categories.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    categories.truncate(MAX_INTEREST_CATEGORIES);
+    categories.into_iter().map(|(category, _)| category.to_string()).collect()
+}
+
+/// Builds the ranking signals for one learner from their enrollments and lesson progress.
+/// Their level is the one they study most, the higher one on a tie; their interests are
+/// the categories they study most.
+pub async fn personalization(services: &AppServices, user_id: Uuid) -> Result<Personalization, ApiError> {
+    let enrollments: Vec<Enrollment> = services
+        .postgress
+        .query("SELECT * FROM enrollments WHERE user_id = $1::uuid")
+        .bind(user_id)
+        .fetch_all()
+        .await?;
+
+    let completions: Vec<CourseLessonCompletions> = services
+        .postgress
+        .query(
+            r#"
+            SELECT user_id, course_id, COUNT(*) AS completed_lessons
+            FROM lesson_progress
+            WHERE user_id = $1::uuid
+            GROUP BY user_id, course_id
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all()
+        .await?;
+
+    let studied_ids: Vec<String> = enrollments
+        .iter()
+        .map(|e| e.course_id.clone())
+        .chain(completions.iter().map(|c| c.course_id.clone()))
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+
+    if studied_ids.is_empty() {
+        return Ok(Personalization::default());
+    }
+
+    let studied: Vec<Course> = services.neo4j
+        .query_nodes("MATCH (c:Course) WHERE c.id IN $ids RETURN c")
+        .param("ids", studied_ids.clone())
+        .fetch_key("c")
+        .fetch()
+        .await?;
+
+    let counts = lesson_counts(services, studied_ids).await?;
+    let completed_course_ids: HashSet<String> = completions
+        .iter()
+        .filter(|c| is_finished(c, &counts))
+        .map(|c| c.course_id.clone())
+        .collect();
+
+    let level = dominant_level(studied.iter().map(|c| c.level.as_str()));
+    let categories = top_categories(studied.iter().map(|c| c.category.as_str()));
+
+    let completed_titles: Vec<String> = studied
+        .iter()
+        .filter(|c| completed_course_ids.contains(&c.id))
+        .map(|c| c.title.to_lowercase())
+        .collect();
+    let completed_course_ids: Vec<String> = completed_course_ids.into_iter().collect();
+
+    // Course prerequisites name other courses by title.
+    let ready_course_ids = if completed_titles.is_empty() {
+        Vec::new()
+    } else {
+        services.neo4j
+            .query_nodes(
+                "MATCH (c:Course)
+                 WHERE size(coalesce(c.prerequisites, [])) > 0
+                   AND all(p IN c.prerequisites WHERE toLower(p) IN $titles)
+                   AND NOT c.id IN $completed
+                 RETURN c.id AS id"
+            )
+            .param("titles", completed_titles)
+            .param("completed", completed_course_ids.clone())
+            .fetch_all::<CourseId>()
+            .await?
+            .into_iter()
+            .map(|c| c.0)
+            .collect()
+    };
+
+    Ok(Personalization {
+        level,
+        categories,
+        completed_course_ids,
+        ready_course_ids,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dominant_level_is_the_most_studied() {
+        let level = dominant_level(["beginner", "Intermediate", "intermediate", "advanced"]);
+        assert_eq!(level.as_deref(), Some("intermediate"));
+    }
+
+    #[test]
+    fn dominant_level_prefers_the_higher_level_on_a_tie() {
+        let level = dominant_level(["beginner", "advanced", "intermediate", "advanced", "beginner"]);
+        assert_eq!(level.as_deref(), Some("advanced"));
+    }
+
+    #[test]
+    fn dominant_level_breaks_full_ties_alphabetically() {
+        // Unknown levels rank with beginner.
+        assert_eq!(dominant_level(["expert", "beginner"]).as_deref(), Some("beginner"));
+        assert_eq!(dominant_level(["expert", "basics"]).as_deref(), Some("basics"));
+    }
+
+    #[test]
+    fn dominant_level_ignores_courses_without_one() {
+        assert_eq!(dominant_level(["", ""]), None);
+        assert_eq!(dominant_level(["", "advanced", ""]).as_deref(), Some("advanced"));
+    }
+
+    #[test]
+    fn top_categories_rank_by_count_then_name() {
+        let categories = top_categories(["Rust", "Kafka", "Go", "Kafka", "", "Go", "Actix"]);
+        assert_eq!(categories, vec!["Go", "Kafka", "Actix", "Rust"]);
+    }
+
+    #[test]
+    fn top_categories_keep_at_most_the_limit() {
+        let categories = top_categories(["a", "b", "c", "d", "e", "f", "g", "g"]);
+        assert_eq!(categories.len(), MAX_INTEREST_CATEGORIES);
+        assert_eq!(categories, vec!["g", "a", "b", "c", "d"]);
+    }
+}
@@ -0,0 +1,547 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt};
+use ring::hmac;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use uuid::Uuid;
+
+use crate::dtos::storage::PresignedQuery;
+use crate::error::ApiError;
+use crate::traits::object_storage::{ObjectHead, ObjectPage, ObjectStorage, ObjectStream, ObjectSummary, UploadedPart};
+
+const LIST_PAGE_SIZE: usize = 1000;
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+const MULTIPART_DIR: &str = ".multipart";
+
+/// Everything a presigned URL commits to; the signature covers all of it.
+struct SignedRequest<'a> {
+    method: &'a str,
+    bucket: &'a str,
+    key: &'a str,
+    expires: i64,
+    upload_id: Option<&'a str>,
+    part_number: Option<i32>,
+    content_type: Option<&'a str>,
+    content_length: Option<i64>,
+}
+
+impl SignedRequest<'_> {
+    fn payload(&self) -> String {
+        format!(
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}",
+            self.method,
+            self.bucket,
+            self.key,
+            self.expires,
+            self.upload_id.unwrap_or_default(),
+            self.part_number.map(|p| p.to_string()).unwrap_or_default(),
+            self.content_type.unwrap_or_default(),
+            self.content_length.map(|l| l.to_string()).unwrap_or_default()
+        )
+    }
+}
+
+fn query_escape(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            b => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+pub struct LocalStorage {
+    root: PathBuf,
+    public_url: String,
+    signing_key: hmac::Key,
+}
+
+fn io_error(context: &str, e: std::io::Error) -> ApiError {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        ApiError::NotFound(context.to_string())
+    } else {
+        ApiError::Storage(format!("{}: {}", context, e))
+    }
+}
+
+pub fn content_type_for(key: &str) -> &'static str {
+    match key.rsplit('.').next().map(|e| e.to_ascii_lowercase()).as_deref() {
+        Some("mp4") => "video/mp4",
+        Some("mov") => "video/quicktime",
+        Some("webm") => "video/webm",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("png") => "image/png",
+        Some("webp") => "image/webp",
+        Some("pdf") => "application/pdf",
+        _ => "application/octet-stream",
+    }
+}
+
+fn e_tag_for(metadata: &std::fs::Metadata) -> String {
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|m| m.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    format!("\"{:x}-{:x}\"", metadata.len(), modified)
+}
+
+fn modified_at(metadata: &std::fs::Metadata) -> Option<DateTime<Utc>> {
+    metadata.modified().ok().map(DateTime::<Utc>::from)
+}
+
+impl LocalStorage {
+    pub fn new(root: PathBuf, public_url: String, signing_key: &[u8]) -> Self {
+        Self {
+            root,
+            public_url: public_url.trim_end_matches('/').to_string(),
+            signing_key: hmac::Key::new(hmac::HMAC_SHA256, signing_key),
+        }
+    }
+
+    fn checked_join(&self, parts: &[&str]) -> Result<PathBuf, ApiError> {
+        let mut path = self.root.clone();
+        for part in parts {
+            let relative = Path::new(part);
+            if part.is_empty() || !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+                return Err(ApiError::BadRequest(format!("Invalid storage path {}", part)));
+            }
+            path.push(relative);
+        }
+        Ok(path)
+    }
+
+    pub fn object_path(&self, bucket: &str, key: &str) -> Result<PathBuf, ApiError> {
+        if bucket.starts_with('.') {
+            return Err(ApiError::BadRequest(format!("Invalid bucket {}", bucket)));
+        }
+        self.checked_join(&[bucket, key])
+    }
+
+    fn upload_dir(&self, upload_id: &str) -> Result<PathBuf, ApiError> {
+        Uuid::parse_str(upload_id)
+            .map_err(|_| ApiError::BadRequest(format!("Invalid upload id {}", upload_id)))?;
+        self.checked_join(&[MULTIPART_DIR, upload_id])
+    }
+
+    pub fn part_path(&self, upload_id: &str, part_number: i32) -> Result<PathBuf, ApiError> {
+        Ok(self.upload_dir(upload_id)?.join(format!("{:05}.part", part_number)))
+    }
+
+    fn presign(&self, request: SignedRequest) -> String {
+        let signature = hex::encode(hmac::sign(&self.signing_key, request.payload().as_bytes()).as_ref());
+
+        let mut url = format!(
+            "{}/storage/{}/{}?expires={}&signature={}",
+            self.public_url, request.bucket, request.key, request.expires, signature
+        );
+        if let (Some(upload_id), Some(part_number)) = (request.upload_id, request.part_number) {
+            url.push_str(&format!("&upload_id={}&part_number={}", upload_id, part_number));
+        }
+        if let Some(content_type) = request.content_type {
+            url.push_str(&format!("&content_type={}", query_escape(content_type)));
+        }
+        if let Some(content_length) = request.content_length {
+            url.push_str(&format!("&content_length={}", content_length));
+        }
+        url
+    }
+
+    fn expires_at(expires_in: Duration) -> i64 {
+        Utc::now().timestamp() + expires_in.as_secs() as i64
+    }
+
+    /// Checks the signature and expiry only; the caller must still hold the request to
+    /// the signed `content_type` and `content_length`.
+    pub fn verify(&self, method: &str, bucket: &str, key: &str, query: &PresignedQuery) -> bool {
+        if query.expires < Utc::now().timestamp() {
+            return false;
+        }
+
+        let request = SignedRequest {
+            method,
+            bucket,
+            key,
+            expires: query.expires,
+            upload_id: query.upload_id.as_deref(),
+            part_number: query.part_number,
+            content_type: query.content_type.as_deref(),
+            content_length: query.content_length,
+        };
+
+        match hex::decode(&query.signature) {
+            Ok(signature) => hmac::verify(&self.signing_key, request.payload().as_bytes(), &signature).is_ok(),
+            Err(_) => false,
+        }
+    }
+
+    pub async fn write_stream<S, E>(&self, path: &Path, mut stream: S) -> Result<(i64, String), ApiError>
+    where
+        S: Stream<Item = Result<Bytes, E>> + Unpin,
+        E: std::fmt::Display,
+    {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| io_error("Failed to create directory", e))?;
+        }
+
+        let tmp_path = path.with_extension(format!("{}.tmp", Uuid::new_v4()));
+        let mut file = tokio::fs::File::create(&tmp_path)
+            .await
+            .map_err(|e| io_error("Failed to create file", e))?;
+
+        let mut written: i64 = 0;
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|e| ApiError::Storage(format!("Upload interrupted: {}", e)))?;
+            written += chunk.len() as i64;
+            file.write_all(&chunk)
+                .await
+                .map_err(|e| io_error("Failed to write file", e))?;
+        }
+
+        file.flush().await.map_err(|e| io_error("Failed to flush file", e))?;
+        tokio::fs::rename(&tmp_path, path)
+            .await
+            .map_err(|e| io_error("Failed to move file into place", e))?;
+
+        let metadata = tokio::fs::metadata(path)
+            .await
+            .map_err(|e| io_error("Failed to stat file", e))?;
+        Ok((written, e_tag_for(&metadata)))
+    }
+
+    /// Queues the entries of `dir`, keyed relative to `root` with directories ending in `/`,
+    /// largest key first so that popping visits them in key order.
+    async fn push_entries(
+        root: &Path,
+        dir: &Path,
+        pending: &mut Vec<(String, PathBuf, std::fs::Metadata)>,
+    ) -> Result<(), ApiError> {
+        let mut entries = match tokio::fs::read_dir(dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(io_error("Failed to list directory", e)),
+        };
+
+        let mut children = Vec::new();
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|e| io_error("Failed to list directory", e))?
+        {
+            let metadata = entry
+                .metadata()
+                .await
+                .map_err(|e| io_error("Failed to stat file", e))?;
+            let path = entry.path();
+            let mut key = path
+                .strip_prefix(root)
+                .map(|p| p.to_string_lossy().replace('\\', "/"))
+                .unwrap_or_default();
+            if metadata.is_dir() {
+                key.push('/');
+            }
+            children.push((key, path, metadata));
+        }
+
+        children.sort_by(|a, b| b.0.cmp(&a.0));
+        pending.extend(children);
+        Ok(())
+    }
+
+    /// Lists objects under `root` in key order, starting after `after`, and stops once
+    /// `limit` objects are found. Directories that cannot hold a matching key are skipped
+    /// without being read.
+    async fn walk(&self, root: &Path, prefix: &str, after: Option<&str>, limit: usize) -> Result<Vec<ObjectSummary>, ApiError> {
+        let mut objects = Vec::new();
+        let mut pending = Vec::new();
+        Self::push_entries(root, root, &mut pending).await?;
+
+        while let Some((key, path, metadata)) = pending.pop() {
+            if objects.len() >= limit {
+                break;
+            }
+
+            if metadata.is_dir() {
+                let under_prefix = key.starts_with(prefix) || prefix.starts_with(key.as_str());
+                let before_start = after.map(|a| key.as_str() <= a && !a.starts_with(key.as_str())).unwrap_or(false);
+                if under_prefix && !before_start {
+                    Self::push_entries(root, &path, &mut pending).await?;
+                }
+            } else if path.extension().map(|e| e != "tmp").unwrap_or(true)
+                && key.starts_with(prefix)
+                && after.map(|a| key.as_str() > a).unwrap_or(true)
+            {
+                objects.push(ObjectSummary {
+                    key,
+                    size: metadata.len() as i64,
+                    last_modified: modified_at(&metadata),
+                });
+            }
+        }
+
+        Ok(objects)
+    }
+}
+
+#[async_trait]
+impl ObjectStorage for LocalStorage {
+    async fn create_bucket(&self, bucket: &str) -> Result<(), ApiError> {
+        let path = self.checked_join(&[bucket])?;
+        tokio::fs::create_dir_all(path)
+            .await
+            .map_err(|e| io_error(&format!("Failed to create bucket {}", bucket), e))
+    }
+
+    async fn put(&self, bucket: &str, key: &str, data: Vec<u8>, _content_type: Option<&str>) -> Result<(), ApiError> {
+        let path = self.object_path(bucket, key)?;
+        let stream = futures::stream::iter(vec![Ok::<_, std::io::Error>(Bytes::from(data))]);
+        self.write_stream(&path, stream).await?;
+        Ok(())
+    }
+
+    async fn get(&self, bucket: &str, key: &str, range: Option<(i64, i64)>) -> Result<ObjectStream, ApiError> {
+        let path = self.object_path(bucket, key)?;
+        let not_found = format!("Object {}/{} not found", bucket, key);
+
+        let mut file = tokio::fs::File::open(&path)
+            .await
+            .map_err(|e| io_error(&not_found, e))?;
+        let size = file
+            .metadata()
+            .await
+            .map_err(|e| io_error(&not_found, e))?
+            .len() as i64;
+
+        let (start, end) = range.unwrap_or((0, size - 1));
+        if start > 0 {
+            file.seek(std::io::SeekFrom::Start(start as u64))
+                .await
+                .map_err(|e| io_error("Failed to seek", e))?;
+        }
+
+        let content_length = (end - start + 1).max(0);
+        let body = futures::stream::unfold((file, content_length), |(mut file, remaining)| async move {
+            if remaining <= 0 {
+                return None;
+            }
+
+            let mut buf = vec![0u8; READ_CHUNK_SIZE.min(remaining as usize)];
+            match file.read(&mut buf).await {
+                Ok(0) => None,
+                Ok(n) => {
+                    buf.truncate(n);
+                    Some((Ok(Bytes::from(buf)), (file, remaining - n as i64)))
+                }
+                Err(e) => Some((Err(io_error("Failed to read file", e)), (file, 0))),
+            }
+        })
+        .boxed();
+
+        Ok(ObjectStream {
+            content_type: Some(content_type_for(key).to_string()),
+            content_length,
+            body,
+        })
+    }
+
+    async fn head(&self, bucket: &str, key: &str) -> Result<Option<ObjectHead>, ApiError> {
+        let path = self.object_path(bucket, key)?;
+
+        match tokio::fs::metadata(&path).await {
+            Ok(metadata) if metadata.is_file() => Ok(Some(ObjectHead {
+                size: metadata.len() as i64,
+                content_type: Some(content_type_for(key).to_string()),
+                e_tag: Some(e_tag_for(&metadata)),
+                last_modified: modified_at(&metadata),
+            })),
+            Ok(_) => Ok(None),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(io_error("Failed to stat file", e)),
+        }
+    }
+
+    async fn delete(&self, bucket: &str, key: &str) -> Result<(), ApiError> {
+        let path = self.object_path(bucket, key)?;
+
+        match tokio::fs::remove_file(&path).await {
+            Ok(_) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(io_error("Failed to delete file", e)),
+        }
+    }
+
+    async fn list(
+        &self,
+        bucket: &str,
+        prefix: Option<&str>,
+        continuation_token: Option<String>,
+    ) -> Result<ObjectPage, ApiError> {
+        let bucket_dir = self.checked_join(&[bucket])?;
+        // One extra object tells us whether another page follows.
+        let mut objects = self
+            .walk(&bucket_dir, prefix.unwrap_or_default(), continuation_token.as_deref(), LIST_PAGE_SIZE + 1)
+            .await?;
+
+        let next_token = if objects.len() > LIST_PAGE_SIZE {
+            objects.truncate(LIST_PAGE_SIZE);
+            objects.last().map(|o| o.key.clone())
+        } else {
+            None
+        };
+
+        Ok(ObjectPage { objects, next_token })
+    }
+
+    async fn presign_get(&self, bucket: &str, key: &str, expires_in: Duration) -> Result<String, ApiError> {
+        self.object_path(bucket, key)?;
+        Ok(self.presign(SignedRequest {
+            method: "GET",
+            bucket,
+            key,
+            expires: Self::expires_at(expires_in),
+            upload_id: None,
+            part_number: None,
+            content_type: None,
+            content_length: None,
+        }))
+    }
+
+    async fn presign_put(
+        &self,
+        bucket: &str,
+        key: &str,
+        content_type: &str,
+        content_length: i64,
+        expires_in: Duration,
+    ) -> Result<String, ApiError> {
+        self.object_path(bucket, key)?;
+        Ok(self.presign(SignedRequest {
+            method: "PUT",
+            bucket,
+            key,
+            expires: Self::expires_at(expires_in),
+            upload_id: None,
+            part_number: None,
+            content_type: Some(content_type),
+            content_length: Some(content_length),
+        }))
+    }
+
+    async fn create_multipart_upload(&self, bucket: &str, key: &str, _content_type: &str) -> Result<String, ApiError> {
+        self.object_path(bucket, key)?;
+        let upload_id = Uuid::new_v4().to_string();
+        tokio::fs::create_dir_all(self.upload_dir(&upload_id)?)
+            .await
+            .map_err(|e| io_error("Failed to start multipart upload", e))?;
+        Ok(upload_id)
+    }
+
+    async fn presign_upload_part(
+        &self,
+        bucket: &str,
+        key: &str,
+        upload_id: &str,
+        part_number: i32,
+        expires_in: Duration,
+    ) -> Result<String, ApiError> {
+        self.upload_dir(upload_id)?;
+        Ok(self.presign(SignedRequest {
+            method: "PUT",
+            bucket,
+            key,
+            expires: Self::expires_at(expires_in),
+            upload_id: Some(upload_id),
+            part_number: Some(part_number),
+            content_type: None,
+            content_length: None,
+        }))
+    }
+
+    async fn upload_part(
+        &self,
+        _bucket: &str,
+        _key: &str,
+        upload_id: &str,
+        part_number: i32,
+        data: Vec<u8>,
+    ) -> Result<String, ApiError> {
+        let path = self.part_path(upload_id, part_number)?;
+        let stream = futures::stream::iter(vec![Ok::<_, std::io::Error>(Bytes::from(data))]);
+        let (_, e_tag) = self.write_stream(&path, stream).await?;
+        Ok(e_tag)
+    }
+
+    async fn list_parts(&self, _bucket: &str, _key: &str, upload_id: &str) -> Result<Vec<UploadedPart>, ApiError> {
+        let dir = self.upload_dir(upload_id)?;
+        let mut parts = Vec::new();
+
+        for object in self.walk(&dir, "", None, usize::MAX).await? {
+            let Some(part_number) = object
+                .key
+                .strip_suffix(".part")
+                .and_then(|n| n.parse::<i32>().ok())
+            else {
+                continue;
+            };
+
+            let metadata = tokio::fs::metadata(dir.join(&object.key))
+                .await
+                .map_err(|e| io_error("Failed to stat part", e))?;
+
+            parts.push(UploadedPart {
+                part_number,
+                e_tag: e_tag_for(&metadata),
+                size: object.size,
+            });
+        }
+
+        Ok(parts)
+    }
+
+    async fn complete_multipart_upload(
+        &self,
+        bucket: &str,
+        key: &str,
+        upload_id: &str,
+        parts: &[UploadedPart],
+    ) -> Result<(), ApiError> {
+        let path = self.object_path(bucket, key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| io_error("Failed to create directory", e))?;
+        }
+
+        let mut output = tokio::fs::File::create(&path)
+            .await
+            .map_err(|e| io_error("Failed to create file", e))?;
+
+        for part in parts {
+            let mut input = tokio::fs::File::open(self.part_path(upload_id, part.part_number)?)
+                .await
+                .map_err(|e| io_error(&format!("Missing part {}", part.part_number), e))?;
+            tokio::io::copy(&mut input, &mut output)
+                .await
+                .map_err(|e| io_error("Failed to assemble upload", e))?;
+        }
+
+        output.flush().await.map_err(|e| io_error("Failed to flush file", e))?;
+        self.abort_multipart_upload(bucket, key, upload_id).await
+    }
+
+    async fn abort_multipart_upload(&self, _bucket: &str, _key: &str, upload_id: &str) -> Result<(), ApiError> {
+        match tokio::fs::remove_dir_all(self.upload_dir(upload_id)?).await {
+            Ok(_) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(io_error("Failed to abort multipart upload", e)),
+        }
+    }
+}
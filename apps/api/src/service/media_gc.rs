@@ -0,0 +1,194 @@
+use std::collections::HashSet;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use crate::error::ApiError;
+use crate::models::image::{cover_key, variant_key, ImageFormat, VARIANT_WIDTHS};
+use crate::models::programe::{Course, Lesson};
+use crate::models::upload::split_reference;
+use crate::service::image_variants::source_version;
+use crate::service::neo4j_service::Neo4jService;
+use crate::traits::object_storage::ObjectStorage;
+
+pub const MEDIA_BUCKETS: [&str; 4] = ["courses", "documents", "videos", "images"];
+pub const DEFAULT_GRACE_PERIOD_HOURS: i64 = 72;
+
+pub struct ReconcileOptions {
+    pub delete: bool,
+    pub grace_period: Duration,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OrphanedObject {
+    pub bucket: String,
+    pub key: String,
+    pub size: i64,
+    pub last_modified: Option<DateTime<Utc>>,
+    pub deleted: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DanglingReference {
+    pub label: String,
+    pub node_id: String,
+    pub field: String,
+    pub reference: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MediaReconcileReport {
+    pub scanned_objects: usize,
+    pub referenced_objects: usize,
+    pub orphans: Vec<OrphanedObject>,
+    pub dangling_references: Vec<DanglingReference>,
+    pub deleted_objects: usize,
+    pub reclaimed_bytes: i64,
+}
+
+struct MediaReference {
+    label: &'static str,
+    node_id: String,
+    field: &'static str,
+    reference: String,
+    bucket: String,
+    key: String,
+}
+
+/// Resolves a stored reference the same way the seed data lays objects out:
+/// `bucket/key` when the prefix is a media bucket, otherwise a key in the field's default bucket.
+pub fn resolve(reference: &str, default_bucket: &str) -> (String, String) {
+    match split_reference(reference) {
+        Some((bucket, key)) if MEDIA_BUCKETS.contains(&bucket) => (bucket.to_string(), key.to_string()),
+        _ => (default_bucket.to_string(), reference.to_string()),
+    }
+}
+
+fn reference(label: &'static str, node_id: &str, field: &'static str, value: &str, default_bucket: &str) -> MediaReference {
+    let (bucket, key) = resolve(value, default_bucket);
+    MediaReference {
+        label,
+        node_id: node_id.to_string(),
+        field,
+        reference: value.to_string(),
+        bucket,
+        key,
+    }
+}
+
+async fn collect_references(neo4j: &Neo4jService) -> Result<Vec<MediaReference>, ApiError> {
+    let courses: Vec<Course> = neo4j
+        .query_nodes("MATCH (c:Course) RETURN c")
+        .fetch_key("c")
+        .fetch()
+        .await?;
+
+    let lessons: Vec<Lesson> = neo4j
+        .query_nodes("MATCH (l:Lesson) RETURN l")
+        .fetch_key("l")
+        .fetch()
+        .await?;
+
+    let mut references = Vec::new();
+
+    for course in &courses {
+        if !course.cover.is_empty() {
+            references.push(reference("Course", &course.id, "cover", &course.cover, "images"));
+        }
+        for document in &course.documents {
+            references.push(reference("Course", &course.id, "documents", document, "documents"));
+        }
+    }
+
+    for lesson in lessons.iter().filter(|l| !l.video.is_empty()) {
+        references.push(reference("Lesson", &lesson.id, "video", &lesson.video, "videos"));
+    }
+
+    Ok(references)
+}
+
+/// Compares every object in the media buckets against Neo4j references.
+/// Orphans are only deleted when `options.delete` is set and they are older than the grace period.
+pub async fn reconcile_media(
+    storage: &dyn ObjectStorage,
+    neo4j: &Neo4jService,
+    options: &ReconcileOptions,
+) -> Result<MediaReconcileReport, ApiError> {
+    let references = collect_references(neo4j).await?;
+
+    let mut referenced: HashSet<(String, String)> = references
+        .iter()
+        .map(|r| (r.bucket.clone(), r.key.clone()))
+        .collect();
+
+    // Generated cover variants live alongside the cover and are kept as long as it is,
+    // but only those rendered from its current version.
+    for r in references.iter().filter(|r| r.field == "cover") {
+        let Some(key) = cover_key(&r.reference) else {
+            continue;
+        };
+        let Some(head) = storage.head("images", key).await? else {
+            continue;
+        };
+        let version = source_version(&head);
+        for width in VARIANT_WIDTHS {
+            for format in ImageFormat::ALL {
+                referenced.insert(("images".to_string(), variant_key(key, &version, width, format)));
+            }
+        }
+    }
+
+    let mut existing: HashSet<(String, String)> = HashSet::new();
+    let mut orphans = Vec::new();
+    let mut scanned_objects = 0;
+    let cutoff = Utc::now() - options.grace_period;
+
+    for bucket in MEDIA_BUCKETS {
+        for object in storage.list_all(bucket, None).await? {
+            scanned_objects += 1;
+            let id = (bucket.to_string(), object.key.clone());
+
+            if referenced.contains(&id) {
+                existing.insert(id);
+                continue;
+            }
+
+            let expired = object.last_modified.map(|m| m < cutoff).unwrap_or(false);
+            let deleted = if options.delete && expired {
+                storage.delete(bucket, &object.key).await?;
+                true
+            } else {
+                false
+            };
+
+            orphans.push(OrphanedObject {
+                bucket: bucket.to_string(),
+                key: object.key,
+                size: object.size,
+                last_modified: object.last_modified,
+                deleted,
+            });
+        }
+    }
+
+    let dangling_references = references
+        .into_iter()
+        .filter(|r| !existing.contains(&(r.bucket.clone(), r.key.clone())))
+        .map(|r| DanglingReference {
+            label: r.label.to_string(),
+            node_id: r.node_id,
+            field: r.field.to_string(),
+            reference: r.reference,
+        })
+        .collect();
+
+    let deleted: Vec<&OrphanedObject> = orphans.iter().filter(|o| o.deleted).collect();
+
+    Ok(MediaReconcileReport {
+        scanned_objects,
+        referenced_objects: existing.len(),
+        deleted_objects: deleted.len(),
+        reclaimed_bytes: deleted.iter().map(|o| o.size).sum(),
+        orphans,
+        dangling_references,
+    })
+}
@@ -1,20 +1,55 @@
 mod neo4j_query;
 mod postgres_query;
 
+use std::sync::Arc;
+use crate::config::{Config, StorageConfig};
+use crate::traits::object_storage::ObjectStorage;
+
 pub mod neo4j_service;
 pub mod postgres_service;
 pub mod redis_cache_service;
 pub mod kafka_service;
 pub mod s3_service;
+pub mod local_storage;
+pub mod video_probe;
+pub mod image_variants;
+pub mod media_gc;
+pub mod document_text;
+pub mod content_index;
+pub mod index_mappings;
 pub mod opensearch;
+pub mod programs_reindex;
+pub mod related_courses;
+pub mod learner_profile;
+pub mod index_queue;
+pub mod search_analytics;
+pub mod search_fallback;
 pub mod jwt_service;
+pub mod notification_service;
+pub mod email_channel;
 
 pub struct AppServices {
     pub cache: redis_cache_service::CacheService,
     pub neo4j: neo4j_service::Neo4jService,
     pub kafka: kafka_service::KafkaService,
     pub postgress: postgres_service::PostgresService,
-    pub s3: s3_service::S3Service,
+    pub storage: Arc<dyn ObjectStorage>,
+    pub local_storage: Option<Arc<local_storage::LocalStorage>>,
     pub opensearch: opensearch::OpenSearchService,
-    pub jwt_service: jwt_service::JwtService
+    pub jwt_service: jwt_service::JwtService,
+    pub notifications: notification_service::NotificationService,
+}
+
+pub fn build_storage(config: &Config) -> (Arc<dyn ObjectStorage>, Option<Arc<local_storage::LocalStorage>>) {
+    match &config.storage {
+        StorageConfig::S3(client) => (Arc::new(s3_service::S3Service::new(client.clone())), None),
+        StorageConfig::Local { root, public_url } => {
+            let local = Arc::new(local_storage::LocalStorage::new(
+                root.clone(),
+                public_url.clone(),
+                config.jwt_secret.as_bytes(),
+            ));
+            (local.clone(), Some(local))
+        }
+    }
 }
@@ -65,14 +65,14 @@ impl Neo4jService {
             rel_type = rel_type,
         );
 
-        if let Some(ref p) = props {
-            if !p.is_empty() {
-                let set_clauses = p.iter()
-                    .map(|(k, _)| format!("r.{k} = ${k}"))
-                    .collect::<Vec<_>>()
-                    .join(", ");
-                cypher = format!("{cypher} SET {set_clauses}");
-            }
+        if let Some(ref p) = props
+            && !p.is_empty()
+        {
+            let set_clauses = p.iter()
+                .map(|(k, _)| format!("r.{k} = ${k}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            cypher = format!("{cypher} SET {set_clauses}");
         }
 
         cypher = format!("{cypher} RETURN r");
@@ -87,7 +87,7 @@ impl Neo4jService {
             }
         }
 
-        let _ = self.graph.run(q).await?;
+        self.graph.run(q).await?;
         Ok(())
     }
 }
@@ -0,0 +1,46 @@
+use crate::{dtos::notifications::NewNotification, error::ApiError, models::{auth::User, notification::Notification}, service::postgres_service::PostgresService, traits::notification_channel::NotificationChannel};
+
+pub struct NotificationService {
+    channels: Vec<Box<dyn NotificationChannel>>,
+}
+
+impl NotificationService {
+    pub fn new(channels: Vec<Box<dyn NotificationChannel>>) -> Self {
+        Self { channels }
+    }
+
+    pub async fn notify(
+        &self,
+        postgres: &PostgresService,
+        user: &User,
+        new: NewNotification,
+    ) -> Result<Option<Notification>, ApiError> {
+        let notification: Option<Notification> = postgres
+            .query(
+                r#"
+                INSERT INTO notifications (user_id, kind, title, body, resource_id, dedup_key)
+                VALUES ($1::uuid, $2, $3, $4, NULLIF($5, ''), NULLIF($6, ''))
+                ON CONFLICT (dedup_key) DO NOTHING
+                RETURNING *
+                "#,
+            )
+            .bind(user.id)
+            .bind(&new.kind)
+            .bind(&new.title)
+            .bind(&new.body)
+            .bind(new.resource_id.unwrap_or_default())
+            .bind(new.dedup_key.unwrap_or_default())
+            .fetch_optional()
+            .await?;
+
+        if let Some(n) = &notification {
+            for channel in &self.channels {
+                if let Err(e) = channel.deliver(user, n).await {
+                    eprintln!("⚠ {} delivery failed for notification {}: {}", channel.name(), n.id, e);
+                }
+            }
+        }
+
+        Ok(notification)
+    }
+}
@@ -1,141 +1,516 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 use opensearch::{
-    DeleteParts, IndexParts, OpenSearch, SearchParts 
+    http::{headers::HeaderMap, request::JsonBody, response::Response, Method, StatusCode}, params::Refresh, BulkParts, DeleteByQueryParts, DeleteParts, IndexParts, OpenSearch, SearchParts 
 };
 use serde::de::DeserializeOwned;
 use serde_json::{json, Value};
-use crate::{error::ApiError, models::{programe::{Course}, search::{FilterCondition, SearchResponse}}};
+use crate::{error::ApiError, models::{programe::{Course}, search::{ContentDocument, DocumentMatch, FacetBucket, FilterCondition, HighlightOptions, PageRequest, Personalization, ProgramsIndex, SearchCursor, SearchFacets, SearchResponse, Suggestion, Suggestions}, search_query::ParsedQuery}, service::{document_text::ExtractedDocument, index_mappings::{content_index_body, documents_index_body, load_synonyms, programs_index_body}}};
+
+pub const PROGRAMS_ALIAS: &str = "programs";
+pub const PROGRAMS_INDEX_PREFIX: &str = "programs_v";
+pub const DOCUMENTS_INDEX: &str = "program_documents";
+pub const CONTENT_INDEX: &str = "program_content";
+
+/// Fields filtered through `post_filter` so their facet counts ignore their own selection.
+const FACET_FIELDS: [&str; 8] = [
+    "status",
+    "category",
+    "level",
+    "instructor",
+    "prerequisites",
+    "total_duration_minutes",
+    "rating",
+    "featured",
+];
+/// Keyword fields holding display values, so `level:beginner` still finds "Beginner".
+const CASE_INSENSITIVE_FIELDS: [&str; 4] = ["status", "category", "level", "instructor"];
+const TERMS_FACET_SIZE: i64 = 50;
+const BULK_CHUNK_SIZE: usize = 500;
+const PIT_KEEP_ALIVE: &str = "2m";
+const LEVEL_BOOST: f64 = 1.5;
+const CATEGORY_BOOST: f64 = 1.3;
+const PREREQUISITES_MET_BOOST: f64 = 1.4;
+const COMPLETED_WEIGHT: f64 = 0.2;
+
+/// Deleting a document that is already gone is not a failure, so retries of a
+/// delete settle instead of failing forever.
+fn deleted_or_missing(response: Response, context: &str) -> Result<(), ApiError> {
+    if response.status_code() == StatusCode::NOT_FOUND {
+        return Ok(());
+    }
+    response
+        .error_for_status_code()
+        .map(|_| ())
+        .map_err(|e| ApiError::Internal(format!("{}: {}", context, e)))
+}
+
+fn course_document(course: &Course) -> Value {
+    json!({
+        "id": course.id,
+        "title": course.title,
+        "description": course.description,
+        "status": course.status,
+        "category": course.category,
+        "level": course.level,
+        "rating": course.rating,
+        "instructor": course.instructor,
+        "featured": course.featured,
+        "cover": course.cover,
+        "cover_variants": course.cover_variants,
+        "prerequisites": course.prerequisites,
+        "documents": course.documents,
+        "total_duration_minutes": course.total_duration_minutes,
+    })
+}
+
+fn filter_clause(filter: &FilterCondition) -> Value {
+    match filter {
+        FilterCondition::Term { field, value } if CASE_INSENSITIVE_FIELDS.contains(&field.as_str()) => json!({
+            "term": {
+                field: { "value": value, "case_insensitive": true }
+            }
+        }),
+        FilterCondition::Term { field, value } => json!({
+            "term": {
+                field: value
+            }
+        }),
+        FilterCondition::Range { field, gte, lte, gt, lt } => {
+            let mut range_obj = json!({});
+            if let Some(v) = gte {
+                range_obj[field.clone()]["gte"] = json!(v);
+            }
+            if let Some(v) = lte {
+                range_obj[field.clone()]["lte"] = json!(v);
+            }
+            if let Some(v) = gt {
+                range_obj[field.clone()]["gt"] = json!(v);
+            }
+            if let Some(v) = lt {
+                range_obj[field.clone()]["lt"] = json!(v);
+            }
+            json!({
+                "range": range_obj
+            })
+        }
+        FilterCondition::FloatRange { field, gte, lte, gt, lt } => {
+            let mut bounds = json!({});
+            for (op, bound) in [("gte", gte), ("lte", lte), ("gt", gt), ("lt", lt)] {
+                if let Some(v) = bound {
+                    bounds[op] = json!(v);
+                }
+            }
+            json!({
+                "range": {
+                    field: bounds
+                }
+            })
+        }
+        FilterCondition::Match { field, value } => json!({
+            "match": {
+                field: value
+            }
+        }),
+        FilterCondition::Terms { field, values } => json!({
+            "terms": {
+                field: values
+            }
+        }),
+        FilterCondition::Exists { field } => json!({
+            "exists": { "field": field }
+        }),
+        FilterCondition::Prefix { field, value } => json!({
+            "prefix": {
+                field: {
+                    "value": value,
+                    "case_insensitive": CASE_INSENSITIVE_FIELDS.contains(&field.as_str())
+                }
+            }
+        }),
+        FilterCondition::Wildcard { field, value } => json!({
+            "wildcard": {
+                field: {
+                    "value": value,
+                    "case_insensitive": CASE_INSENSITIVE_FIELDS.contains(&field.as_str())
+                }
+            }
+        }),
+        FilterCondition::And { conditions } => json!({
+            "bool": {
+                "filter": conditions.iter().map(filter_clause).collect::<Vec<_>>()
+            }
+        }),
+        FilterCondition::Or { conditions } => json!({
+            "bool": {
+                "should": conditions.iter().map(filter_clause).collect::<Vec<_>>(),
+                "minimum_should_match": 1
+            }
+        }),
+        FilterCondition::Not { condition } => json!({
+            "bool": {
+                "must_not": [filter_clause(condition)]
+            }
+        }),
+    }
+}
+
+fn facet_body(field: &str) -> Value {
+    match field {
+        "total_duration_minutes" => json!({
+            "range": {
+                "field": field,
+                "ranges": [
+                    { "key": "under_30", "to": 30 },
+                    { "key": "30_to_60", "from": 30, "to": 60 },
+                    { "key": "60_to_120", "from": 60, "to": 120 },
+                    { "key": "120_to_300", "from": 120, "to": 300 },
+                    { "key": "over_300", "from": 300 }
+                ]
+            }
+        }),
+        "rating" => json!({
+            "range": {
+                "field": field,
+                "ranges": [
+                    { "key": "4.5_and_up", "from": 4.5 },
+                    { "key": "4_and_up", "from": 4.0 },
+                    { "key": "3_and_up", "from": 3.0 }
+                ]
+            }
+        }),
+        "featured" => json!({
+            "filter": { "term": { "featured": true } }
+        }),
+        _ => json!({
+            "terms": { "field": field, "size": TERMS_FACET_SIZE }
+        }),
+    }
+}
+
+/// Each facet is computed under every active facet filter except its own.
+fn facet_aggregations(facet_filters: &[FilterCondition]) -> Value {
+    let mut aggs = serde_json::Map::new();
+
+    for field in FACET_FIELDS {
+        let others: Vec<Value> = facet_filters
+            .iter()
+            .filter(|f| f.field() != Some(field))
+            .map(filter_clause)
+            .collect();
+
+        aggs.insert(field.to_string(), json!({
+            "filter": { "bool": { "filter": others } },
+            "aggs": { "values": facet_body(field) }
+        }));
+    }
+
+    Value::Object(aggs)
+}
+
+fn parse_buckets(aggregations: &Value, field: &str) -> Vec<FacetBucket> {
+    aggregations[field]["values"]["buckets"]
+        .as_array()
+        .map(|buckets| {
+            buckets
+                .iter()
+                .map(|b| FacetBucket {
+                    key: b["key_as_string"]
+                        .as_str()
+                        .or_else(|| b["key"].as_str())
+                        .map(|k| k.to_string())
+                        .unwrap_or_else(|| b["key"].to_string()),
+                    count: b["doc_count"].as_i64().unwrap_or(0),
+                    from: b["from"].as_f64(),
+                    to: b["to"].as_f64(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn parse_facets(aggregations: &Value) -> SearchFacets {
+    SearchFacets {
+        status: parse_buckets(aggregations, "status"),
+        category: parse_buckets(aggregations, "category"),
+        level: parse_buckets(aggregations, "level"),
+        instructor: parse_buckets(aggregations, "instructor"),
+        prerequisites: parse_buckets(aggregations, "prerequisites"),
+        total_duration_minutes: parse_buckets(aggregations, "total_duration_minutes"),
+        rating: parse_buckets(aggregations, "rating"),
+        featured: aggregations["featured"]["values"]["doc_count"].as_i64().unwrap_or(0),
+    }
+}
+
+fn highlight_body(options: &HighlightOptions, fields: &[&str]) -> Value {
+    let fields: serde_json::Map<String, Value> = fields
+        .iter()
+        .map(|f| (f.split('^').next().unwrap_or(f).to_string(), json!({})))
+        .collect();
+
+    json!({
+        "encoder": "html",
+        "pre_tags": [options.pre_tag],
+        "post_tags": [options.post_tag],
+        "fragment_size": options.fragment_size,
+        "number_of_fragments": options.number_of_fragments,
+        "fields": fields
+    })
+}
+
+/// Multiplies relevance by a weight for each personal signal a course matches;
+/// courses matching none keep their plain score.
+fn personalized_query(query: Value, personalization: &Personalization) -> Value {
+    let mut functions = Vec::new();
+
+    if let Some(level) = &personalization.level {
+        functions.push(json!({
+            "filter": { "term": { "level": { "value": level, "case_insensitive": true } } },
+            "weight": LEVEL_BOOST
+        }));
+    }
+    if !personalization.categories.is_empty() {
+        let categories: Vec<Value> = personalization.categories
+            .iter()
+            .map(|category| json!({ "term": { "category": { "value": category, "case_insensitive": true } } }))
+            .collect();
+        functions.push(json!({
+            "filter": { "bool": { "should": categories, "minimum_should_match": 1 } },
+            "weight": CATEGORY_BOOST
+        }));
+    }
+    if !personalization.ready_course_ids.is_empty() {
+        functions.push(json!({
+            "filter": { "ids": { "values": personalization.ready_course_ids } },
+            "weight": PREREQUISITES_MET_BOOST
+        }));
+    }
+    if !personalization.completed_course_ids.is_empty() {
+        functions.push(json!({
+            "filter": { "ids": { "values": personalization.completed_course_ids } },
+            "weight": COMPLETED_WEIGHT
+        }));
+    }
+
+    json!({
+        "function_score": {
+            "query": query,
+            "functions": functions,
+            "score_mode": "multiply",
+            "boost_mode": "multiply"
+        }
+    })
+}
+
+fn completion(prefix: &str, field: &str, size: i64) -> Value {
+    json!({
+        "prefix": prefix,
+        "completion": {
+            "field": field,
+            "size": size,
+            "skip_duplicates": true,
+            "fuzzy": { "fuzziness": "AUTO", "min_length": 3 }
+        }
+    })
+}
+
+fn parse_suggestions(json: &Value, name: &str, with_id: bool) -> Vec<Suggestion> {
+    json["suggest"][name][0]["options"]
+        .as_array()
+        .map(|options| {
+            options
+                .iter()
+                .filter_map(|o| {
+                    Some(Suggestion {
+                        text: o["text"].as_str()?.to_string(),
+                        id: if with_id { o["_id"].as_str().map(|id| id.to_string()) } else { None },
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
 
 pub struct OpenSearchService {
     client: Arc<OpenSearch>,
+    synonyms_path: String,
 }
 
 impl OpenSearchService {
-    pub fn new(client: Arc<OpenSearch>) -> Self {
-        Self { client }
+    pub fn new(client: Arc<OpenSearch>, synonyms_path: String) -> Self {
+        Self { client, synonyms_path }
+    }
+
+    pub fn synonyms_path(&self) -> &str {
+        &self.synonyms_path
     }
 
+    /// The programs index definition with the synonyms currently on disk.
+    pub async fn programs_index_definition(&self) -> Result<Value, ApiError> {
+        let synonyms = load_synonyms(&self.synonyms_path).await?;
+        Ok(programs_index_body(&synonyms))
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub async fn search<T>(
         &self,
         index: &str,
-        query_text: &str,
+        query: &ParsedQuery,
         fields: Vec<&str>,
-        from: i64,
-        size: i64,
+        include_ids: &[String],
+        highlight: Option<&HighlightOptions>,
+        personalization: Option<&Personalization>,
+        page: &PageRequest,
     ) -> Result<SearchResponse<T>, ApiError>
     where
         T: DeserializeOwned + Send + Sync,
     {
-        let query = json!({
-            "query": {
-                "multi_match": {
-                    "query": query_text,
-                    "fields": fields,
-                    "type": "best_fields",
-                    "operator": "or",
-                    "fuzziness": "AUTO"
+        // Courses found through their documents or lessons count as matching the text.
+        let text_clause = |text: &str, kind: &str| {
+            let mut multi_match = json!({
+                "query": text,
+                "fields": fields,
+                "type": kind
+            });
+            if kind != "phrase" {
+                multi_match["operator"] = json!("or");
+                multi_match["fuzziness"] = json!("AUTO");
+            }
+
+            json!({
+                "bool": {
+                    "should": [
+                        { "multi_match": multi_match },
+                        { "ids": { "values": include_ids } }
+                    ],
+                    "minimum_should_match": 1
                 }
-            },
-            "from": from,
-            "size": size,
-            "sort": ["_score"]
+            })
+        };
+
+        let mut must: Vec<Value> = query.phrases
+            .iter()
+            .map(|phrase| text_clause(phrase, "phrase"))
+            .collect();
+        if !query.text.is_empty() {
+            must.push(text_clause(&query.text, "best_fields"));
+        }
+
+        let must_not: Vec<Value> = query.excluded
+            .iter()
+            .map(|term| json!({
+                "multi_match": { "query": term, "fields": fields, "type": "phrase" }
+            }))
+            .chain(query.excluded_filters.iter().map(filter_clause))
+            .collect();
+
+        let personalization = personalization.filter(|p| !p.is_empty());
+        if must.is_empty() && personalization.is_some() {
+            // Filter-only queries score 0, which no weight could lift.
+            must.push(json!({ "match_all": {} }));
+        }
+
+        let mut bool_query = json!({
+            "bool": {
+                "must": must,
+                "filter": query.filters.iter().map(filter_clause).collect::<Vec<_>>(),
+                "must_not": must_not
+            }
         });
+        if let Some(personalization) = personalization {
+            bool_query = personalized_query(bool_query, personalization);
+        }
 
-        let response = self.client
-            .search(SearchParts::Index(&[index]))
-            .body(query)
-            .send()
-            .await
-            .map_err(|e| ApiError::Internal(format!("OpenSearch request failed: {}", e)))?;
+        let mut body = json!({ "query": bool_query });
 
-        let json = response
-            .json::<Value>()
-            .await
-            .map_err(|e| ApiError::Internal(format!("Failed to parse response: {}", e)))?;
+        if let Some(options) = highlight {
+            body["highlight"] = highlight_body(options, &fields);
+        }
 
-        self.parse_search_response(json)
+        self.send_page(index, body, json!(["_score"]), page).await
     }
 
     pub async fn filter<T>(
         &self,
         index: &str,
         filters: Vec<FilterCondition>,
-        from: i64,
-        size: i64,
+        page: &PageRequest,
         sort_by: Option<&str>,
         sort_order: Option<&str>,
     ) -> Result<SearchResponse<T>, ApiError>
     where
         T: DeserializeOwned + Send + Sync,
     {
-        let mut bool_query = json!({
+        let (facet_filters, query_filters): (Vec<FilterCondition>, Vec<FilterCondition>) = filters
+            .into_iter()
+            .partition(|f| f.field().map(|field| FACET_FIELDS.contains(&field)).unwrap_or(false));
+
+        let bool_query = json!({
             "bool": {
-                "must": []
+                "must": query_filters.iter().map(filter_clause).collect::<Vec<_>>()
             }
         });
 
-        let must_array = bool_query["bool"]["must"].as_array_mut().unwrap();
-
-        for filter in filters {
-            match filter {
-                FilterCondition::Term { field, value } => {
-                    must_array.push(json!({
-                        "term": {
-                            field: value
-                        }
-                    }));
-                }
-                FilterCondition::Range { field, gte, lte, gt, lt } => {
-                    let mut range_obj = json!({});
-                    if let Some(v) = gte {
-                        range_obj[field.clone()]["gte"] = json!(v);
-                    }
-                    if let Some(v) = lte {
-                        range_obj[field.clone()]["lte"] = json!(v);
-                    }
-                    if let Some(v) = gt {
-                        range_obj[field.clone()]["gt"] = json!(v);
-                    }
-                    if let Some(v) = lt {
-                        range_obj[field.clone()]["lt"] = json!(v);
-                    }
-                    must_array.push(json!({
-                        "range": range_obj
-                    }));
-                }
-                FilterCondition::Match { field, value } => {
-                    must_array.push(json!({
-                        "match": {
-                            field: value
-                        }
-                    }));
-                }
-                FilterCondition::Terms { field, values } => {
-                    must_array.push(json!({
-                        "terms": {
-                            field: values
-                        }
-                    }));
-                }
-            }
-        }
-
-        let mut query_body = json!({
+        let body = json!({
             "query": bool_query,
-            "from": from,
-            "size": size
+            "post_filter": {
+                "bool": {
+                    "filter": facet_filters.iter().map(filter_clause).collect::<Vec<_>>()
+                }
+            },
+            "aggs": facet_aggregations(&facet_filters)
         });
 
-        if let Some(field) = sort_by {
-            let order = sort_order.unwrap_or("asc");
-            query_body["sort"] = json!([
-                { field: { "order": order } }
-            ]);
+        let sort = match sort_by {
+            Some(field) => json!([
+                { field: { "order": sort_order.unwrap_or("asc") } }
+            ]),
+            None => json!(["_score"]),
+        };
+
+        self.send_page(index, body, sort, page).await
+    }
+
+    /// Runs a search one page at a time. Sorting always ends on `id` so `search_after` has a
+    /// total order to resume from; with a point in time every page reads the same snapshot.
+    async fn send_page<T>(
+        &self,
+        index: &str,
+        mut body: Value,
+        mut sort: Value,
+        page: &PageRequest,
+    ) -> Result<SearchResponse<T>, ApiError>
+    where
+        T: DeserializeOwned,
+    {
+        if let Some(sort) = sort.as_array_mut() {
+            sort.push(json!({ "id": "asc" }));
         }
+        body["sort"] = sort;
+        body["size"] = json!(page.size);
 
-        let response = self.client
-            .search(SearchParts::Index(&[index]))
-            .body(query_body)
+        match &page.cursor {
+            Some(cursor) => body["search_after"] = json!(cursor.search_after),
+            None => body["from"] = json!(page.from),
+        }
+
+        let pit_id = match page.cursor.as_ref().and_then(|c| c.pit_id.clone()) {
+            Some(pit_id) => Some(pit_id),
+            None if page.point_in_time => Some(self.open_point_in_time(index).await?),
+            None => None,
+        };
+
+        let indices = [index];
+        let request = match &pit_id {
+            Some(pit_id) => {
+                body["pit"] = json!({ "id": pit_id, "keep_alive": PIT_KEEP_ALIVE });
+                self.client.search(SearchParts::None)
+            }
+            None => self.client.search(SearchParts::Index(&indices)),
+        };
+
+        let response = request
+            .body(body)
             .send()
             .await
             .map_err(|e| ApiError::Internal(format!("OpenSearch request failed: {}", e)))?;
@@ -145,7 +520,70 @@ impl OpenSearchService {
             .await
             .map_err(|e| ApiError::Internal(format!("Failed to parse response: {}", e)))?;
 
-        self.parse_search_response(json)
+        let mut search_response = self.parse_search_response::<T>(json.clone())?;
+
+        let last_sort = json["hits"]["hits"]
+            .as_array()
+            .filter(|hits| hits.len() as i64 >= page.size)
+            .and_then(|hits| hits.last())
+            .and_then(|hit| hit["sort"].as_array().cloned());
+        let pit_id = json["pit_id"].as_str().map(|id| id.to_string()).or(pit_id);
+
+        match last_sort {
+            Some(search_after) => {
+                search_response.next_cursor = Some(SearchCursor { search_after, pit_id });
+            }
+            None => {
+                // Last page: the snapshot is no longer needed.
+                if let Some(pit_id) = pit_id
+                    && let Err(e) = self.close_point_in_time(&pit_id).await
+                {
+                    eprintln!("⚠ Failed to close point in time: {}", e);
+                }
+            }
+        }
+
+        Ok(search_response)
+    }
+
+    async fn open_point_in_time(&self, index: &str) -> Result<String, ApiError> {
+        let response = self.client
+            .send(
+                Method::Post,
+                &format!("/{}/_search/point_in_time", index),
+                HeaderMap::new(),
+                Some(&[("keep_alive", PIT_KEEP_ALIVE)]),
+                Option::<JsonBody<Value>>::None,
+                None,
+            )
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to open point in time: {}", e)))?;
+
+        let json = response
+            .json::<Value>()
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to parse response: {}", e)))?;
+
+        json["pit_id"]
+            .as_str()
+            .map(|id| id.to_string())
+            .ok_or_else(|| ApiError::Internal(format!("OpenSearch did not return a point in time: {}", json)))
+    }
+
+    async fn close_point_in_time(&self, pit_id: &str) -> Result<(), ApiError> {
+        self.client
+            .send(
+                Method::Delete,
+                "/_search/point_in_time",
+                HeaderMap::new(),
+                Option::<&[(&str, &str)]>::None,
+                Some(JsonBody::new(json!({ "pit_id": [pit_id] }))),
+                None,
+            )
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to close point in time: {}", e)))?;
+
+        Ok(())
     }
 
     fn parse_search_response<T>(&self, json: Value) -> Result<SearchResponse<T>, ApiError>
@@ -182,7 +620,12 @@ impl OpenSearchService {
         let results: Result<Vec<T>, _> = hits
             .iter()
             .map(|hit| {
-                serde_json::from_value(hit["_source"].clone())
+                let mut source = hit["_source"].clone();
+                if let (Some(highlight), Some(object)) = (hit.get("highlight"), source.as_object_mut()) {
+                    object.insert("highlights".to_string(), highlight.clone());
+                }
+
+                serde_json::from_value(source)
                     .map_err(|e| ApiError::Internal(format!("Failed to deserialize hit: {}", e)))
             })
             .collect();
@@ -190,88 +633,220 @@ impl OpenSearchService {
         Ok(SearchResponse {
             total,
             results: results?,
+            facets: json.get("aggregations").map(parse_facets),
+            next_cursor: None,
         })
     }
 
-    pub async fn index_course(&self, course: &Course) -> Result<(), ApiError> {
-        if !self.index_exists("programs").await? {
-            self.create_programs_index().await?;
+    /// Courses whose title, description and category read like the given one, with their scores.
+    pub async fn more_like_this(&self, course_id: &str, size: i64) -> Result<Vec<(String, f64)>, ApiError> {
+        if !self.index_exists(PROGRAMS_ALIAS).await? {
+            return Ok(Vec::new());
         }
 
         let body = json!({
-            "id": course.id,
-            "title": course.title,
-            "description": course.description,
-            "status": course.status,
-            "cover": course.cover,
-            "prerequisites": course.prerequisites,
-            "documents": course.documents,
-            "total_duration_minutes": course.total_duration_minutes,
+            "query": {
+                "bool": {
+                    "must": {
+                        "more_like_this": {
+                            "fields": ["title", "description", "category"],
+                            "like": [{ "_id": course_id }],
+                            "min_term_freq": 1,
+                            "min_doc_freq": 1,
+                            "max_query_terms": 25
+                        }
+                    },
+                    "must_not": { "ids": { "values": [course_id] } }
+                }
+            },
+            "_source": ["id"],
+            "size": size
         });
 
+        let json = self.send_search(PROGRAMS_ALIAS, body).await?;
+
+        Ok(json["hits"]["hits"]
+            .as_array()
+            .map(|hits| {
+                hits.iter()
+                    .filter_map(|hit| {
+                        let id = hit["_id"].as_str()?.to_string();
+                        Some((id, hit["_score"].as_f64().unwrap_or(0.0)))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    pub async fn index_course(&self, course: &Course) -> Result<(), ApiError> {
+        self.ensure_programs_index().await?;
+
         self.client
-            .index(IndexParts::IndexId("programs", &course.id))
-            .body(body)
+            .index(IndexParts::IndexId(PROGRAMS_ALIAS, &course.id))
+            .body(course_document(course))
             .send()
             .await
-            .map_err(|e| ApiError::Internal(format!("Failed to index course: {}", e)))?;
+            .map_err(|e| ApiError::Internal(format!("Failed to index course: {}", e)))?
+            .error_for_status_code()
+            .map_err(|e| ApiError::Internal(format!("Failed to index course {}: {}", course.id, e)))?;
 
         Ok(())
     }
 
     pub async fn delete_course(&self, course_id: &str) -> Result<(), ApiError> {
-        self.client
-            .delete(DeleteParts::IndexId("programs", course_id))
+        let response = self.client
+            .delete(DeleteParts::IndexId(PROGRAMS_ALIAS, course_id))
             .send()
             .await
             .map_err(|e| ApiError::Internal(format!("Failed to delete course from index: {}", e)))?;
 
-        Ok(())
+        deleted_or_missing(response, &format!("Failed to delete course {} from index", course_id))
     }
 
-    pub async fn create_programs_index(&self) -> Result<(), ApiError> {
-        let body = json!({
-            "settings": {
-                "number_of_shards": 1,
-                "number_of_replicas": 0,
-                "analysis": {
-                    "analyzer": {
-                        "default": {
-                            "type": "standard"
-                        }
-                    }
-                }
-            },
-            "mappings": {
-                "properties": {
-                    "id": { "type": "keyword" },
-                    "title": {
-                        "type": "text",
-                        "fields": {
-                            "keyword": { "type": "keyword" }
-                        }
-                    },
-                    "description": { "type": "text" },
-                    "status": { "type": "keyword" },
-                    "cover": { "type": "keyword" },
-                    "prerequisites": { "type": "keyword" },
-                    "documents": { "type": "keyword" },
-                    "total_duration_minutes": { "type": "integer" },
+    /// Creates the first versioned programs index and points the alias at it.
+    pub async fn ensure_programs_index(&self) -> Result<(), ApiError> {
+        if self.index_exists(PROGRAMS_ALIAS).await? {
+            return Ok(());
+        }
+
+        // A versioned index without the alias means an earlier attempt stopped half way.
+        let indices = self.programs_indices().await?;
+        let index = match indices.last() {
+            Some(latest) => latest.name.clone(),
+            None => self.create_programs_version(1).await?,
+        };
+
+        self.swap_programs_alias(&index, &indices).await
+    }
+
+    pub async fn create_programs_version(&self, version: u32) -> Result<String, ApiError> {
+        let index = format!("{}{}", PROGRAMS_INDEX_PREFIX, version);
+
+        self.client
+            .indices()
+            .create(opensearch::indices::IndicesCreateParts::Index(&index))
+            .body(self.programs_index_definition().await?)
+            .send()
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to create programs index: {}", e)))?
+            .error_for_status_code()
+            .map_err(|e| ApiError::Internal(format!("Failed to create {}: {}", index, e)))?;
+
+        Ok(index)
+    }
+
+    /// Every index that holds programs, including a pre-alias concrete `programs` index.
+    pub async fn programs_indices(&self) -> Result<Vec<ProgramsIndex>, ApiError> {
+        let pattern = format!("{}*", PROGRAMS_INDEX_PREFIX);
+        let response = self.client
+            .indices()
+            .get(opensearch::indices::IndicesGetParts::Index(&[pattern.as_str(), PROGRAMS_ALIAS]))
+            .ignore_unavailable(true)
+            .allow_no_indices(true)
+            .send()
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to list programs indices: {}", e)))?;
+
+        if response.status_code().as_u16() == 404 {
+            return Ok(Vec::new());
+        }
+
+        let json = response
+            .json::<Value>()
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to parse response: {}", e)))?;
+
+        let mut indices: Vec<ProgramsIndex> = json
+            .as_object()
+            .map(|map| {
+                map.iter()
+                    .map(|(name, index)| ProgramsIndex {
+                        name: name.clone(),
+                        version: name
+                            .strip_prefix(PROGRAMS_INDEX_PREFIX)
+                            .and_then(|v| v.parse().ok())
+                            .unwrap_or(0),
+                        aliased: index["aliases"].get(PROGRAMS_ALIAS).is_some(),
+                        checksum: index["mappings"]["_meta"]["checksum"].as_str().map(|c| c.to_string()),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        indices.sort_by_key(|index| index.version);
+        Ok(indices)
+    }
+
+    /// Points the alias at `index` in a single request, dropping it from whatever served it before.
+    /// A concrete index squatting on the alias name is removed in the same atomic step.
+    pub async fn swap_programs_alias(&self, index: &str, previous: &[ProgramsIndex]) -> Result<(), ApiError> {
+        let mut actions: Vec<Value> = previous
+            .iter()
+            .filter(|p| p.name != index)
+            .filter_map(|p| {
+                if p.name == PROGRAMS_ALIAS {
+                    Some(json!({ "remove_index": { "index": p.name } }))
+                } else if p.aliased {
+                    Some(json!({ "remove": { "index": p.name, "alias": PROGRAMS_ALIAS } }))
+                } else {
+                    None
                 }
-            }
-        });
+            })
+            .collect();
+        actions.push(json!({ "add": { "index": index, "alias": PROGRAMS_ALIAS } }));
 
         self.client
             .indices()
-            .create(opensearch::indices::IndicesCreateParts::Index("programs"))
-            .body(body)
+            .update_aliases()
+            .body(json!({ "actions": actions }))
             .send()
             .await
-            .map_err(|e| ApiError::Internal(format!("Failed to create programs index: {}", e)))?;
+            .map_err(|e| ApiError::Internal(format!("Failed to update programs alias: {}", e)))?
+            .error_for_status_code()
+            .map_err(|e| ApiError::Internal(format!("Failed to point {} at {}: {}", PROGRAMS_ALIAS, index, e)))?;
 
         Ok(())
     }
 
+    pub async fn bulk_index_courses(&self, index: &str, courses: &[Course]) -> Result<usize, ApiError> {
+        for chunk in courses.chunks(BULK_CHUNK_SIZE) {
+            let mut body: Vec<JsonBody<Value>> = Vec::with_capacity(chunk.len() * 2);
+            for course in chunk {
+                body.push(json!({ "index": { "_id": course.id } }).into());
+                body.push(course_document(course).into());
+            }
+
+            let response = self.client
+                .bulk(BulkParts::Index(index))
+                .refresh(Refresh::WaitFor)
+                .body(body)
+                .send()
+                .await
+                .map_err(|e| ApiError::Internal(format!("Failed to bulk index courses: {}", e)))?;
+
+            let json = response
+                .json::<Value>()
+                .await
+                .map_err(|e| ApiError::Internal(format!("Failed to parse response: {}", e)))?;
+
+            if json["errors"].as_bool().unwrap_or(false) {
+                return Err(ApiError::Internal(format!("Some courses failed to index into {}", index)));
+            }
+        }
+
+        Ok(courses.len())
+    }
+
+    pub async fn delete_index(&self, index: &str) -> Result<(), ApiError> {
+        self.client
+            .indices()
+            .delete(opensearch::indices::IndicesDeleteParts::Index(&[index]))
+            .send()
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to delete index {}: {}", index, e)))?;
+
+        Ok(())
+    }
 
     pub async fn index_exists(&self, index: &str) -> Result<bool, ApiError> {
         let response = self.client
@@ -283,6 +858,346 @@ impl OpenSearchService {
 
         Ok(response.status_code().is_success())
     }
+
+    pub async fn create_documents_index(&self) -> Result<(), ApiError> {
+        let body = documents_index_body();
+
+        self.client
+            .indices()
+            .create(opensearch::indices::IndicesCreateParts::Index(DOCUMENTS_INDEX))
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to create documents index: {}", e)))?;
+
+        Ok(())
+    }
+
+    pub async fn index_document_pages(&self, course_id: &str, document: &ExtractedDocument) -> Result<usize, ApiError> {
+        if !self.index_exists(DOCUMENTS_INDEX).await? {
+            self.create_documents_index().await?;
+        }
+
+        let mut body: Vec<JsonBody<Value>> = Vec::new();
+        for (index, content) in document.pages.iter().enumerate() {
+            if content.is_empty() {
+                continue;
+            }
+
+            let page = index + 1;
+            body.push(json!({ "index": { "_id": format!("{}:{}:{}", course_id, document.reference, page) } }).into());
+            body.push(json!({
+                "course_id": course_id,
+                "document": document.reference,
+                "document_title": document.title,
+                "page": page,
+                "content": content,
+            }).into());
+        }
+
+        let pages = body.len() / 2;
+        if pages == 0 {
+            return Ok(0);
+        }
+
+        let response = self.client
+            .bulk(BulkParts::Index(DOCUMENTS_INDEX))
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to index document pages: {}", e)))?;
+
+        let json = response
+            .json::<Value>()
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to parse response: {}", e)))?;
+
+        if json["errors"].as_bool().unwrap_or(false) {
+            return Err(ApiError::Internal(format!(
+                "Some pages of {} failed to index",
+                document.reference
+            )));
+        }
+
+        Ok(pages)
+    }
+
+    pub async fn delete_course_documents(&self, course_id: &str) -> Result<(), ApiError> {
+        if !self.index_exists(DOCUMENTS_INDEX).await? {
+            return Ok(());
+        }
+
+        self.client
+            .delete_by_query(DeleteByQueryParts::Index(&[DOCUMENTS_INDEX]))
+            .body(json!({
+                "query": { "term": { "course_id": course_id } }
+            }))
+            .send()
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to delete document pages: {}", e)))?
+            .error_for_status_code()
+            .map_err(|e| ApiError::Internal(format!("Failed to delete document pages of {}: {}", course_id, e)))?;
+
+        Ok(())
+    }
+
+    /// Best matching page per course for a free-text query, keyed by course id.
+    pub async fn search_documents(
+        &self,
+        query_text: &str,
+        highlight: Option<&HighlightOptions>,
+        size: i64,
+    ) -> Result<HashMap<String, DocumentMatch>, ApiError> {
+        if query_text.trim().is_empty() || !self.index_exists(DOCUMENTS_INDEX).await? {
+            return Ok(HashMap::new());
+        }
+
+        let mut query = json!({
+            "query": {
+                "match": {
+                    "content": {
+                        "query": query_text,
+                        "fuzziness": "AUTO"
+                    }
+                }
+            },
+            "collapse": { "field": "course_id" },
+            "_source": ["course_id", "document", "document_title", "page"],
+            "size": size
+        });
+
+        if let Some(options) = highlight {
+            query["highlight"] = highlight_body(options, &["content"]);
+        }
+
+        let response = self.client
+            .search(SearchParts::Index(&[DOCUMENTS_INDEX]))
+            .body(query)
+            .send()
+            .await
+            .map_err(|e| ApiError::Internal(format!("OpenSearch request failed: {}", e)))?;
+
+        let json = response
+            .json::<Value>()
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to parse response: {}", e)))?;
+
+        let matches = json["hits"]["hits"]
+            .as_array()
+            .map(|hits| {
+                hits.iter()
+                    .filter_map(|hit| {
+                        let source = &hit["_source"];
+                        let course_id = source["course_id"].as_str()?.to_string();
+                        let title = source["document_title"].as_str().unwrap_or_default().to_string();
+                        let page = source["page"].as_i64().unwrap_or(1) as i32;
+
+                        Some((course_id, DocumentMatch {
+                            document: source["document"].as_str().unwrap_or_default().to_string(),
+                            summary: format!("matched in {}, page {}", title, page),
+                            fragments: hit["highlight"]["content"]
+                                .as_array()
+                                .map(|f| f.iter().filter_map(|s| s.as_str().map(|s| s.to_string())).collect())
+                                .unwrap_or_default(),
+                            title,
+                            page,
+                        }))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(matches)
+    }
+
+    pub async fn create_content_index(&self) -> Result<(), ApiError> {
+        let body = content_index_body();
+
+        self.client
+            .indices()
+            .create(opensearch::indices::IndicesCreateParts::Index(CONTENT_INDEX))
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to create content index: {}", e)))?;
+
+        Ok(())
+    }
+
+    pub async fn index_content(&self, document: &ContentDocument) -> Result<(), ApiError> {
+        if !self.index_exists(CONTENT_INDEX).await? {
+            self.create_content_index().await?;
+        }
+
+        self.client
+            .index(IndexParts::IndexId(CONTENT_INDEX, &document.id))
+            .body(document)
+            .send()
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to index content: {}", e)))?
+            .error_for_status_code()
+            .map_err(|e| ApiError::Internal(format!("Failed to index content {}: {}", document.id, e)))?;
+
+        Ok(())
+    }
+
+    pub async fn delete_content(&self, id: &str) -> Result<(), ApiError> {
+        if !self.index_exists(CONTENT_INDEX).await? {
+            return Ok(());
+        }
+
+        let response = self.client
+            .delete(DeleteParts::IndexId(CONTENT_INDEX, id))
+            .send()
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to delete content from index: {}", e)))?;
+
+        deleted_or_missing(response, &format!("Failed to delete content {} from index", id))
+    }
+
+    pub async fn search_content(&self, query_text: &str, size: i64) -> Result<Vec<ContentDocument>, ApiError> {
+        if query_text.trim().is_empty() || !self.index_exists(CONTENT_INDEX).await? {
+            return Ok(vec![]);
+        }
+
+        let query = json!({
+            "query": {
+                "match": {
+                    "title": {
+                        "query": query_text,
+                        "fuzziness": "AUTO"
+                    }
+                }
+            },
+            "size": size
+        });
+
+        let response = self.client
+            .search(SearchParts::Index(&[CONTENT_INDEX]))
+            .body(query)
+            .send()
+            .await
+            .map_err(|e| ApiError::Internal(format!("OpenSearch request failed: {}", e)))?;
+
+        let json = response
+            .json::<Value>()
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to parse response: {}", e)))?;
+
+        Ok(self.parse_search_response::<ContentDocument>(json)?.results)
+    }
+
+    /// Completion suggestions per type; lesson titles come from the content index.
+    pub async fn suggest(&self, prefix: &str, size: i64) -> Result<Suggestions, ApiError> {
+        let mut suggestions = Suggestions::default();
+
+        if self.index_exists(PROGRAMS_ALIAS).await? {
+            let body = json!({
+                "_source": false,
+                "suggest": {
+                    "courses": completion(prefix, "title.suggest", size),
+                    "categories": completion(prefix, "category.suggest", size),
+                    "instructors": completion(prefix, "instructor.suggest", size)
+                }
+            });
+
+            let json = self.send_search(PROGRAMS_ALIAS, body).await?;
+            suggestions.courses = parse_suggestions(&json, "courses", true);
+            suggestions.categories = parse_suggestions(&json, "categories", false);
+            suggestions.instructors = parse_suggestions(&json, "instructors", false);
+        }
+
+        if self.index_exists(CONTENT_INDEX).await? {
+            let mut lessons = completion(prefix, "title.suggest", size);
+            lessons["completion"]["contexts"] = json!({ "kind": ["lesson"] });
+
+            let body = json!({
+                "_source": false,
+                "suggest": { "lessons": lessons }
+            });
+
+            let json = self.send_search(CONTENT_INDEX, body).await?;
+            suggestions.lessons = parse_suggestions(&json, "lessons", true);
+        }
+
+        Ok(suggestions)
+    }
+
+    async fn send_search(&self, index: &str, body: Value) -> Result<Value, ApiError> {
+        let response = self.client
+            .search(SearchParts::Index(&[index]))
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| ApiError::Internal(format!("OpenSearch request failed: {}", e)))?;
+
+        let json = response
+            .json::<Value>()
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to parse response: {}", e)))?;
+
+        if let Some(error) = json.get("error") {
+            return Err(ApiError::Internal(format!("OpenSearch error: {}", error)));
+        }
+
+        Ok(json)
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
 
+    fn weights(query: &Value) -> Vec<f64> {
+        query["function_score"]["functions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["weight"].as_f64().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn personalized_query_wraps_the_query_and_multiplies_scores() {
+        let query = personalized_query(json!({ "match_all": {} }), &Personalization::default());
+
+        assert_eq!(query["function_score"]["query"], json!({ "match_all": {} }));
+        assert_eq!(query["function_score"]["score_mode"], "multiply");
+        assert_eq!(query["function_score"]["boost_mode"], "multiply");
+        assert!(weights(&query).is_empty());
+    }
+
+    #[test]
+    fn personalized_query_adds_a_function_per_signal() {
+        let personalization = Personalization {
+            level: Some("intermediate".to_string()),
+            categories: vec!["Rust".to_string(), "Kafka".to_string()],
+            completed_course_ids: vec!["done".to_string()],
+            ready_course_ids: vec!["next".to_string()],
+        };
+        let query = personalized_query(json!({ "match_all": {} }), &personalization);
+        let functions = &query["function_score"]["functions"];
+
+        assert_eq!(
+            weights(&query),
+            vec![LEVEL_BOOST, CATEGORY_BOOST, PREREQUISITES_MET_BOOST, COMPLETED_WEIGHT]
+        );
+        assert_eq!(functions[0]["filter"]["term"]["level"]["value"], "intermediate");
+        assert_eq!(functions[0]["filter"]["term"]["level"]["case_insensitive"], true);
+        assert_eq!(functions[1]["filter"]["bool"]["should"].as_array().unwrap().len(), 2);
+        assert_eq!(functions[1]["filter"]["bool"]["minimum_should_match"], 1);
+        assert_eq!(functions[2]["filter"]["ids"]["values"], json!(["next"]));
+        assert_eq!(functions[3]["filter"]["ids"]["values"], json!(["done"]));
+    }
+
+    #[test]
+    fn personalized_query_skips_missing_signals() {
+        let personalization = Personalization {
+            completed_course_ids: vec!["done".to_string()],
+            ..Personalization::default()
+        };
+        let query = personalized_query(json!({ "match_all": {} }), &personalization);
+
+        assert_eq!(weights(&query), vec![COMPLETED_WEIGHT]);
+    }
+}
@@ -1,4 +1,4 @@
-use sqlx::PgPool;
+use sqlx::{PgPool, Postgres, Transaction};
 
 use crate::{error::ApiError, traits::pg_model_trait::PgModel};
 
@@ -14,6 +14,16 @@ pub struct PgQuery<'a> {
     pub binds: Vec<String>,
 }
 
+pub struct PgTransaction {
+    pub tx: Transaction<'static, Postgres>,
+}
+
+pub struct PgTxQuery<'a> {
+    pub tx: &'a mut PgTransaction,
+    pub sql: String,
+    pub binds: Vec<String>,
+}
+
 pub struct PgInsert<'a> {
     pub pool: &'a PgPool,
     pub table: String,
@@ -82,6 +92,66 @@ impl<'a> PgQuery<'a> {
     }
 }
 
+impl PgTransaction {
+    pub fn query(&mut self, sql: impl Into<String>) -> PgTxQuery<'_> {
+        PgTxQuery {
+            tx: self,
+            sql: sql.into(),
+            binds: vec![],
+        }
+    }
+
+    pub async fn commit(self) -> Result<(), ApiError> {
+        Ok(self.tx.commit().await?)
+    }
+}
+
+impl<'a> PgTxQuery<'a> {
+    pub fn bind(mut self, value: impl ToString) -> Self {
+        self.binds.push(value.to_string());
+        self
+    }
+
+    pub async fn fetch_all<T>(self) -> Result<Vec<T>, ApiError>
+    where
+        T: PgModel + Unpin,
+    {
+        let mut query = sqlx::query_as::<_, T>(&self.sql);
+
+        for b in self.binds {
+            query = query.bind(b);
+        }
+
+        Ok(query.fetch_all(&mut *self.tx.tx).await?)
+    }
+
+    pub async fn fetch_one<T>(self) -> Result<T, ApiError>
+    where
+        T: PgModel + Unpin,
+    {
+        let mut query = sqlx::query_as::<_, T>(&self.sql);
+
+        for b in self.binds {
+            query = query.bind(b);
+        }
+
+        Ok(query.fetch_one(&mut *self.tx.tx).await?)
+    }
+
+    pub async fn fetch_optional<T>(self) -> Result<Option<T>, ApiError>
+    where
+        T: PgModel + Unpin,
+    {
+        let mut query = sqlx::query_as::<_, T>(&self.sql);
+
+        for b in self.binds {
+            query = query.bind(b);
+        }
+
+        Ok(query.fetch_optional(&mut *self.tx.tx).await?)
+    }
+}
+
 impl<'a> PgInsert<'a> {
     pub fn value(mut self, column: &str, val: impl Into<PgValue>) -> Self {
         self.columns.push(column.to_string());
@@ -1,6 +1,8 @@
 use sqlx::PgPool;
 
+use crate::error::ApiError;
 use crate::service::postgres_query::{PgDelete, PgInsert, PgQuery, PgUpdate};
+pub use crate::service::postgres_query::PgTransaction;
 
 pub struct PostgresService {
     pool: PgPool,
@@ -8,7 +10,7 @@ pub struct PostgresService {
 
 impl PostgresService {
     pub fn new(pool: PgPool)->Self{
-        Self { pool: pool }
+        Self { pool }
     }
 
     pub fn query<'a>(&'a self, sql: impl Into<String>) -> PgQuery<'a> {
@@ -19,6 +21,13 @@ impl PostgresService {
         }
     }
 
+    /// Starts a transaction; dropping it without `commit` rolls everything back.
+    pub async fn begin(&self) -> Result<PgTransaction, ApiError> {
+        Ok(PgTransaction {
+            tx: self.pool.begin().await?,
+        })
+    }
+
     pub fn insert<'a>(&'a self, table: &str) -> PgInsert<'a> {
         PgInsert {
             pool: &self.pool,
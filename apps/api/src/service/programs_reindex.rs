@@ -0,0 +1,89 @@
+use serde::Serialize;
+
+use crate::error::ApiError;
+use crate::models::programe::Course;
+use crate::service::index_mappings::index_checksum;
+use crate::service::neo4j_service::Neo4jService;
+use crate::service::opensearch::{OpenSearchService, PROGRAMS_ALIAS};
+
+#[derive(Debug, Serialize)]
+pub struct ProgramsIndexStatus {
+    pub alias: String,
+    pub index: Option<String>,
+    pub checksum: Option<String>,
+    pub expected_checksum: String,
+    pub reindex_required: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProgramsReindexReport {
+    pub index: String,
+    pub checksum: String,
+    pub courses: usize,
+    pub deleted_indices: Vec<String>,
+}
+
+/// Compares the checksum of the index behind the alias with the current definition and synonyms.
+pub async fn programs_index_status(opensearch: &OpenSearchService) -> Result<ProgramsIndexStatus, ApiError> {
+    let indices = opensearch.programs_indices().await?;
+    let live = indices
+        .iter()
+        .find(|index| index.aliased || index.name == PROGRAMS_ALIAS);
+    let expected_checksum = index_checksum(&opensearch.programs_index_definition().await?);
+
+    Ok(ProgramsIndexStatus {
+        alias: PROGRAMS_ALIAS.to_string(),
+        index: live.map(|index| index.name.clone()),
+        checksum: live.and_then(|index| index.checksum.clone()),
+        reindex_required: live
+            .map(|index| index.checksum.as_deref() != Some(expected_checksum.as_str()))
+            .unwrap_or(false),
+        expected_checksum,
+    })
+}
+
+/// Builds the next `programs_vN` from Neo4j, swaps the alias onto it and drops the older versions.
+/// Searches keep hitting the previous index until the swap, so there is no window without results.
+/// Courses written while the rebuild runs land in the old index and need another reindex to carry over.
+pub async fn reindex_programs(
+    opensearch: &OpenSearchService,
+    neo4j: &Neo4jService,
+) -> Result<ProgramsReindexReport, ApiError> {
+    let previous = opensearch.programs_indices().await?;
+    let version = previous.iter().map(|index| index.version).max().unwrap_or(0) + 1;
+
+    let courses: Vec<Course> = neo4j
+        .query_nodes("MATCH (c:Course) RETURN c")
+        .fetch_key("c")
+        .fetch()
+        .await?;
+
+    let checksum = index_checksum(&opensearch.programs_index_definition().await?);
+    let index = opensearch.create_programs_version(version).await?;
+
+    let indexed = match opensearch.bulk_index_courses(&index, &courses).await {
+        Ok(indexed) => indexed,
+        Err(e) => {
+            opensearch.delete_index(&index).await?;
+            return Err(e);
+        }
+    };
+
+    opensearch.swap_programs_alias(&index, &previous).await?;
+
+    let mut deleted_indices = Vec::new();
+    for old in previous {
+        // The legacy concrete index is already gone: the alias swap removed it.
+        if old.name != PROGRAMS_ALIAS {
+            opensearch.delete_index(&old.name).await?;
+        }
+        deleted_indices.push(old.name);
+    }
+
+    Ok(ProgramsReindexReport {
+        index,
+        checksum,
+        courses: indexed,
+        deleted_indices,
+    })
+}
@@ -72,4 +72,13 @@ impl RedisCache for CacheService {
 
         Ok(())
     }
+
+    async fn increment(&self, key: &str, ttl_seconds: u64) -> Result<i64, ApiError> {
+        let mut con = self.conn().await?;
+        let count: i64 = con.incr(key, 1).await?;
+        if count == 1 {
+            let _: () = con.expire(key, ttl_seconds as i64).await?;
+        }
+        Ok(count)
+    }
 }
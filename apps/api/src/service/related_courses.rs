@@ -0,0 +1,207 @@
+use std::collections::{HashMap, HashSet};
+use neo4rs::Row;
+use sqlx::types::Uuid;
+
+use crate::error::ApiError;
+use crate::models::programe::Course;
+use crate::models::progress::CourseLessonCompletions;
+use crate::models::search::{RelatedCourse, RelatedReason, RelatedSignal};
+use crate::service::learner_profile::{is_finished, lesson_counts};
+use crate::service::AppServices;
+
+const SIMILAR_CONTENT_WEIGHT: f64 = 1.0;
+const SHARED_PREREQUISITE_WEIGHT: f64 = 0.4;
+const MAX_SHARED_PREREQUISITES: usize = 3;
+const SAME_INSTRUCTOR_WEIGHT: f64 = 0.6;
+const COMPLETED_TOGETHER_WEIGHT: f64 = 1.2;
+/// More candidates than requested, so graph signals can reorder what OpenSearch found.
+const CANDIDATE_MULTIPLIER: i64 = 3;
+
+struct GraphSignal {
+    id: String,
+    shared_prerequisites: Vec<String>,
+    same_instructor: bool,
+    instructor: String,
+}
+
+impl TryFrom<Row> for GraphSignal {
+    type Error = ApiError;
+
+    fn try_from(row: Row) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.get("id").map_err(|_| ApiError::Internal("Could not find key id".to_string()))?,
+            shared_prerequisites: row.get("shared").unwrap_or_default(),
+            same_instructor: row.get("same_instructor").unwrap_or(false),
+            instructor: row.get("instructor").unwrap_or_default(),
+        })
+    }
+}
+
+#[derive(Default)]
+struct Candidate {
+    score: f64,
+    reasons: Vec<RelatedReason>,
+}
+
+impl Candidate {
+    fn add(&mut self, signal: RelatedSignal, weight: f64, detail: String) {
+        self.score += weight;
+        self.reasons.push(RelatedReason { signal, detail });
+    }
+}
+
+async fn graph_signals(services: &AppServices, course_id: &str) -> Result<Vec<GraphSignal>, ApiError> {
+    services.neo4j
+        .query_nodes(
+            "MATCH (c:Course {id: $id}), (o:Course)
+             WHERE o.id <> c.id
+             WITH c, o, [p IN coalesce(o.prerequisites, []) WHERE p IN coalesce(c.prerequisites, [])] AS shared
+             WITH o, shared, (c.instructor IS NOT NULL AND c.instructor <> '' AND o.instructor = c.instructor) AS same_instructor
+             WHERE size(shared) > 0 OR same_instructor
+             RETURN o.id AS id, shared, same_instructor, coalesce(o.instructor, '') AS instructor"
+        )
+        .param("id", course_id.to_string())
+        .fetch_all()
+        .await
+}
+
+/// How many learners finished both the course and each other course, where finishing
+/// means every lesson currently in the course is completed.
+async fn completed_together(services: &AppServices, course_id: &str) -> Result<HashMap<String, i64>, ApiError> {
+    let completions: Vec<CourseLessonCompletions> = services
+        .postgress
+        .query(
+            r#"
+            SELECT user_id, course_id, COUNT(*) AS completed_lessons
+            FROM lesson_progress
+            WHERE user_id IN (SELECT DISTINCT user_id FROM lesson_progress WHERE course_id = $1)
+            GROUP BY user_id, course_id
+            "#,
+        )
+        .bind(course_id)
+        .fetch_all()
+        .await?;
+
+    if completions.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let course_ids: Vec<String> = completions
+        .iter()
+        .map(|c| c.course_id.clone())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+
+    let counts = lesson_counts(services, course_ids).await?;
+    let finished = |c: &CourseLessonCompletions| is_finished(c, &counts);
+
+    let finishers: HashSet<Uuid> = completions
+        .iter()
+        .filter(|c| c.course_id == course_id && finished(c))
+        .map(|c| c.user_id)
+        .collect();
+
+    let mut together: HashMap<String, i64> = HashMap::new();
+    for completion in completions.iter().filter(|c| c.course_id != course_id) {
+        if finishers.contains(&completion.user_id) && finished(completion) {
+            *together.entry(completion.course_id.clone()).or_default() += 1;
+        }
+    }
+
+    Ok(together)
+}
+
+/// Ranks other courses by content similarity plus prerequisite, instructor and
+/// co-completion signals, explaining each suggestion.
+pub async fn related_courses(
+    services: &AppServices,
+    course_id: &str,
+    limit: usize,
+) -> Result<Vec<RelatedCourse>, ApiError> {
+    let exists: Vec<Course> = services.neo4j
+        .query_nodes("MATCH (c:Course {id: $id}) RETURN c")
+        .param("id", course_id.to_string())
+        .fetch_key("c")
+        .fetch()
+        .await?;
+    if exists.is_empty() {
+        return Err(ApiError::NotFound(format!("Course {} not found", course_id)));
+    }
+
+    let mut candidates: HashMap<String, Candidate> = HashMap::new();
+
+    let similar = services.opensearch
+        .more_like_this(course_id, limit as i64 * CANDIDATE_MULTIPLIER)
+        .await?;
+    let top_score = similar.iter().map(|(_, score)| *score).fold(0.0, f64::max);
+    for (id, score) in similar {
+        if top_score > 0.0 {
+            candidates.entry(id).or_default().add(
+                RelatedSignal::SimilarContent,
+                SIMILAR_CONTENT_WEIGHT * score / top_score,
+                "Similar title, description or category".to_string(),
+            );
+        }
+    }
+
+    for signal in graph_signals(services, course_id).await? {
+        let candidate = candidates.entry(signal.id).or_default();
+        if !signal.shared_prerequisites.is_empty() {
+            let counted = signal.shared_prerequisites.len().min(MAX_SHARED_PREREQUISITES);
+            candidate.add(
+                RelatedSignal::SharedPrerequisites,
+                SHARED_PREREQUISITE_WEIGHT * counted as f64,
+                format!("Shares prerequisites: {}", signal.shared_prerequisites.join(", ")),
+            );
+        }
+        if signal.same_instructor {
+            candidate.add(
+                RelatedSignal::SameInstructor,
+                SAME_INSTRUCTOR_WEIGHT,
+                format!("Also taught by {}", signal.instructor),
+            );
+        }
+    }
+
+    let together = completed_together(services, course_id).await?;
+    let most_together = together.values().copied().max().unwrap_or(0);
+    for (id, learners) in together {
+        candidates.entry(id).or_default().add(
+            RelatedSignal::CompletedTogether,
+            COMPLETED_TOGETHER_WEIGHT * learners as f64 / most_together as f64,
+            format!(
+                "{} learner{} who finished this course also finished it",
+                learners,
+                if learners == 1 { "" } else { "s" }
+            ),
+        );
+    }
+
+    let mut ranked: Vec<(String, Candidate)> = candidates.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.score.total_cmp(&a.1.score).then_with(|| a.0.cmp(&b.0)));
+    ranked.truncate(limit);
+
+    let ids: Vec<String> = ranked.iter().map(|(id, _)| id.clone()).collect();
+    let mut courses: HashMap<String, Course> = services.neo4j
+        .query_nodes("MATCH (c:Course) WHERE c.id IN $ids RETURN c")
+        .param("ids", ids)
+        .fetch_key("c")
+        .fetch::<Course>()
+        .await?
+        .into_iter()
+        .map(|c| (c.id.clone(), c))
+        .collect();
+
+    // Courses deleted since they were indexed simply drop out.
+    Ok(ranked
+        .into_iter()
+        .filter_map(|(id, candidate)| {
+            courses.remove(&id).map(|course| RelatedCourse {
+                course,
+                score: candidate.score,
+                reasons: candidate.reasons,
+            })
+        })
+        .collect())
+}
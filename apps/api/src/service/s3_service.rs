@@ -1,138 +1,333 @@
+use async_trait::async_trait;
+use aws_sdk_s3::presigning::PresigningConfig;
 use aws_sdk_s3::primitives::ByteStream;
-use std::path::Path;
+use aws_sdk_s3::types::{CompletedMultipartUpload, CompletedPart};
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
 use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::ApiError;
+use crate::traits::object_storage::{ObjectHead, ObjectPage, ObjectStorage, ObjectStream, ObjectSummary, UploadedPart};
 
 pub struct S3Service {
     client: Arc<aws_sdk_s3::Client>,
 }
 
+fn storage_error(context: &str, e: impl std::fmt::Display) -> ApiError {
+    ApiError::Storage(format!("{}: {}", context, e))
+}
+
+fn to_utc(date: Option<&aws_sdk_s3::primitives::DateTime>) -> Option<DateTime<Utc>> {
+    date.and_then(|d| DateTime::from_timestamp(d.secs(), d.subsec_nanos()))
+}
+
+fn presigning(expires_in: Duration) -> Result<PresigningConfig, ApiError> {
+    PresigningConfig::expires_in(expires_in).map_err(|e| storage_error("Invalid presign expiry", e))
+}
+
 impl S3Service {
     pub fn new(client: Arc<aws_sdk_s3::Client>) -> Self {
         Self { client }
     }
+}
 
-    pub async fn create_bucket(&self, bucket_name: &str) -> Result<(), Box<dyn std::error::Error>> {
-
-        let region = self.client.config().region().unwrap().as_ref();
-
-        let req = self.client.create_bucket().bucket(bucket_name);
-
-        match req.send().await {
-            Ok(_) => println!("✓ Bucket '{}' created in region {}", bucket_name, region),
+#[async_trait]
+impl ObjectStorage for S3Service {
+    async fn create_bucket(&self, bucket: &str) -> Result<(), ApiError> {
+        match self.client.create_bucket().bucket(bucket).send().await {
+            Ok(_) => Ok(()),
             Err(e) => {
-                println!("⚠ Bucket '{}' creation result: {} (may already exist)", bucket_name, e);
+                let exists = e
+                    .as_service_error()
+                    .map(|se| se.is_bucket_already_owned_by_you() || se.is_bucket_already_exists())
+                    .unwrap_or(false);
+
+                if exists {
+                    Ok(())
+                } else {
+                    Err(storage_error(&format!("Failed to create bucket {}", bucket), e))
+                }
             }
         }
-
-        println!("Bucket '{}' created, region {}", bucket_name, region);
-        Ok(())
     }
 
-    pub async fn upload_file(
-        &self,
-        bucket_name: &str,
-        key: &str,
-        file_path: &Path,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        let body = ByteStream::from_path(file_path).await?;
-
+    async fn put(&self, bucket: &str, key: &str, data: Vec<u8>, content_type: Option<&str>) -> Result<(), ApiError> {
         self.client
             .put_object()
-            .bucket(bucket_name)
+            .bucket(bucket)
             .key(key)
-            .body(body)
+            .set_content_type(content_type.map(|c| c.to_string()))
+            .body(ByteStream::from(data))
             .send()
-            .await?;
+            .await
+            .map_err(|e| storage_error(&format!("Failed to upload {}/{}", bucket, key), e))?;
 
-        println!("Uploaded {} to {}/{}", file_path.display(), bucket_name, key);
         Ok(())
     }
 
-    pub async fn upload_data(
-        &self,
-        bucket_name: &str,
-        key: &str,
-        data: Vec<u8>,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        let body = ByteStream::from(data);
+    async fn get(&self, bucket: &str, key: &str, range: Option<(i64, i64)>) -> Result<ObjectStream, ApiError> {
+        let resp = self.client
+            .get_object()
+            .bucket(bucket)
+            .key(key)
+            .set_range(range.map(|(start, end)| format!("bytes={}-{}", start, end)))
+            .send()
+            .await
+            .map_err(|e| {
+                if e.as_service_error().map(|se| se.is_no_such_key()).unwrap_or(false) {
+                    ApiError::NotFound(format!("Object {}/{} not found", bucket, key))
+                } else {
+                    storage_error(&format!("Failed to download {}/{}", bucket, key), e)
+                }
+            })?;
+
+        let content_type = resp.content_type().map(|s| s.to_string());
+        let content_length = resp.content_length().unwrap_or(0);
+
+        let body = futures::stream::unfold(resp.body, |mut body| async move {
+            body.next()
+                .await
+                .map(|chunk| (chunk.map_err(|e| storage_error("Failed to read object body", e)), body))
+        })
+        .boxed();
+
+        Ok(ObjectStream {
+            content_type,
+            content_length,
+            body,
+        })
+    }
 
+    async fn head(&self, bucket: &str, key: &str) -> Result<Option<ObjectHead>, ApiError> {
+        match self.client.head_object().bucket(bucket).key(key).send().await {
+            Ok(resp) => Ok(Some(ObjectHead {
+                size: resp.content_length().unwrap_or(0),
+                content_type: resp.content_type().map(|s| s.to_string()),
+                e_tag: resp.e_tag().map(|s| s.to_string()),
+                last_modified: to_utc(resp.last_modified()),
+            })),
+            Err(e) if e.as_service_error().map(|se| se.is_not_found()).unwrap_or(false) => Ok(None),
+            Err(e) => Err(storage_error(&format!("Failed to stat {}/{}", bucket, key), e)),
+        }
+    }
+
+    async fn delete(&self, bucket: &str, key: &str) -> Result<(), ApiError> {
         self.client
-            .put_object()
-            .bucket(bucket_name)
+            .delete_object()
+            .bucket(bucket)
             .key(key)
-            .body(body)
             .send()
-            .await?;
+            .await
+            .map_err(|e| storage_error(&format!("Failed to delete {}/{}", bucket, key), e))?;
 
-        println!("Uploaded data to {}/{}", bucket_name, key);
         Ok(())
     }
 
-    pub async fn download_file(
+    async fn list(
         &self,
-        bucket_name: &str,
-        key: &str,
-    ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        bucket: &str,
+        prefix: Option<&str>,
+        continuation_token: Option<String>,
+    ) -> Result<ObjectPage, ApiError> {
         let resp = self.client
+            .list_objects_v2()
+            .bucket(bucket)
+            .set_prefix(prefix.map(|p| p.to_string()))
+            .set_continuation_token(continuation_token)
+            .send()
+            .await
+            .map_err(|e| storage_error(&format!("Failed to list {}", bucket), e))?;
+
+        let objects = resp
+            .contents()
+            .iter()
+            .filter_map(|obj| {
+                obj.key().map(|k| ObjectSummary {
+                    key: k.to_string(),
+                    size: obj.size().unwrap_or(0),
+                    last_modified: to_utc(obj.last_modified()),
+                })
+            })
+            .collect();
+
+        let next_token = if resp.is_truncated().unwrap_or(false) {
+            resp.next_continuation_token().map(|t| t.to_string())
+        } else {
+            None
+        };
+
+        Ok(ObjectPage { objects, next_token })
+    }
+
+    async fn presign_get(&self, bucket: &str, key: &str, expires_in: Duration) -> Result<String, ApiError> {
+        let request = self.client
             .get_object()
-            .bucket(bucket_name)
+            .bucket(bucket)
             .key(key)
+            .presigned(presigning(expires_in)?)
+            .await
+            .map_err(|e| storage_error("Failed to presign download", e))?;
+
+        Ok(request.uri().to_string())
+    }
+
+    async fn presign_put(
+        &self,
+        bucket: &str,
+        key: &str,
+        content_type: &str,
+        content_length: i64,
+        expires_in: Duration,
+    ) -> Result<String, ApiError> {
+        let request = self.client
+            .put_object()
+            .bucket(bucket)
+            .key(key)
+            .content_type(content_type)
+            .content_length(content_length)
+            .presigned(presigning(expires_in)?)
+            .await
+            .map_err(|e| storage_error("Failed to presign upload", e))?;
+
+        Ok(request.uri().to_string())
+    }
+
+    async fn create_multipart_upload(&self, bucket: &str, key: &str, content_type: &str) -> Result<String, ApiError> {
+        let resp = self.client
+            .create_multipart_upload()
+            .bucket(bucket)
+            .key(key)
+            .content_type(content_type)
             .send()
-            .await?;
+            .await
+            .map_err(|e| storage_error("Failed to start multipart upload", e))?;
+
+        resp.upload_id()
+            .map(|id| id.to_string())
+            .ok_or_else(|| ApiError::Storage("S3 did not return an upload id".to_string()))
+    }
+
+    async fn presign_upload_part(
+        &self,
+        bucket: &str,
+        key: &str,
+        upload_id: &str,
+        part_number: i32,
+        expires_in: Duration,
+    ) -> Result<String, ApiError> {
+        let request = self.client
+            .upload_part()
+            .bucket(bucket)
+            .key(key)
+            .upload_id(upload_id)
+            .part_number(part_number)
+            .presigned(presigning(expires_in)?)
+            .await
+            .map_err(|e| storage_error("Failed to presign part", e))?;
 
-        let data = resp.body.collect().await?;
-        Ok(data.into_bytes().to_vec())
+        Ok(request.uri().to_string())
     }
 
-    pub async fn list_objects(
+    async fn upload_part(
         &self,
-        bucket_name: &str,
-    ) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        bucket: &str,
+        key: &str,
+        upload_id: &str,
+        part_number: i32,
+        data: Vec<u8>,
+    ) -> Result<String, ApiError> {
         let resp = self.client
-            .list_objects_v2()
-            .bucket(bucket_name)
+            .upload_part()
+            .bucket(bucket)
+            .key(key)
+            .upload_id(upload_id)
+            .part_number(part_number)
+            .body(ByteStream::from(data))
             .send()
-            .await?;
+            .await
+            .map_err(|e| storage_error("Failed to upload part", e))?;
 
-        let keys = resp
-            .contents()
-            .iter()
-            .filter_map(|obj| obj.key().map(|k| k.to_string()))
-            .collect();
+        Ok(resp.e_tag().unwrap_or_default().to_string())
+    }
+
+    async fn list_parts(&self, bucket: &str, key: &str, upload_id: &str) -> Result<Vec<UploadedPart>, ApiError> {
+        let mut parts = Vec::new();
+        let mut marker: Option<String> = None;
+
+        loop {
+            let resp = self.client
+                .list_parts()
+                .bucket(bucket)
+                .key(key)
+                .upload_id(upload_id)
+                .set_part_number_marker(marker.clone())
+                .send()
+                .await
+                .map_err(|e| storage_error("Failed to list parts", e))?;
 
-        Ok(keys)
+            parts.extend(resp.parts().iter().map(|p| UploadedPart {
+                part_number: p.part_number().unwrap_or(0),
+                e_tag: p.e_tag().unwrap_or_default().to_string(),
+                size: p.size().unwrap_or(0),
+            }));
+
+            if !resp.is_truncated().unwrap_or(false) {
+                break;
+            }
+            marker = resp.next_part_number_marker().map(|m| m.to_string());
+            if marker.is_none() {
+                break;
+            }
+        }
+
+        Ok(parts)
     }
 
-    pub async fn delete_object(
+    async fn complete_multipart_upload(
         &self,
-        bucket_name: &str,
+        bucket: &str,
         key: &str,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+        upload_id: &str,
+        parts: &[UploadedPart],
+    ) -> Result<(), ApiError> {
+        let completed = CompletedMultipartUpload::builder()
+            .set_parts(Some(
+                parts
+                    .iter()
+                    .map(|p| {
+                        CompletedPart::builder()
+                            .part_number(p.part_number)
+                            .e_tag(&p.e_tag)
+                            .build()
+                    })
+                    .collect(),
+            ))
+            .build();
+
         self.client
-            .delete_object()
-            .bucket(bucket_name)
+            .complete_multipart_upload()
+            .bucket(bucket)
             .key(key)
+            .upload_id(upload_id)
+            .multipart_upload(completed)
             .send()
-            .await?;
+            .await
+            .map_err(|e| storage_error("Failed to complete multipart upload", e))?;
 
-        println!("Deleted {}/{}", bucket_name, key);
         Ok(())
     }
 
-    pub async fn object_exists(
-        &self,
-        bucket_name: &str,
-        key: &str,
-    ) -> Result<bool, Box<dyn std::error::Error>> {
-        match self.client
-            .head_object()
-            .bucket(bucket_name)
+    async fn abort_multipart_upload(&self, bucket: &str, key: &str, upload_id: &str) -> Result<(), ApiError> {
+        self.client
+            .abort_multipart_upload()
+            .bucket(bucket)
             .key(key)
+            .upload_id(upload_id)
             .send()
             .await
-        {
-            Ok(_) => Ok(true),
-            Err(_) => Ok(false),
-        }
+            .map_err(|e| storage_error("Failed to abort multipart upload", e))?;
+
+        Ok(())
     }
 }
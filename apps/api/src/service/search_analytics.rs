@@ -0,0 +1,190 @@
+use std::time::Instant;
+use actix_web::web;
+use sqlx::types::Uuid;
+
+use crate::error::ApiError;
+use crate::models::analytics::{QueryStat, SearchEvent, ZeroResultQuery};
+use crate::models::search::FilterCondition;
+use crate::service::AppServices;
+
+/// Lowercased with whitespace collapsed, so "Async  Rust" and "async rust" count as one query.
+pub fn normalize_query(query: &str) -> String {
+    query.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+/// One search being timed; `record` stores it once the result count is known.
+pub struct SearchLog {
+    endpoint: &'static str,
+    query: String,
+    filters: Option<String>,
+    started: Instant,
+}
+
+impl SearchLog {
+    pub fn start(endpoint: &'static str, query: &str, filters: &[FilterCondition]) -> Self {
+        Self {
+            endpoint,
+            query: query.trim().to_string(),
+            filters: (!filters.is_empty())
+                .then(|| serde_json::to_string(filters).ok())
+                .flatten(),
+            started: Instant::now(),
+        }
+    }
+
+    /// Files the search under another endpoint once that is known, e.g. after personalizing it.
+    pub fn set_endpoint(&mut self, endpoint: &'static str) {
+        self.endpoint = endpoint;
+    }
+
+    /// Stores the search in the background and returns its id for click tracking;
+    /// analytics never slow down or fail the search itself.
+    pub fn record(&self, services: &web::Data<AppServices>, result_count: i64) -> String {
+        let id = Uuid::new_v4();
+        let services = services.clone();
+        let endpoint = self.endpoint;
+        let query = self.query.clone();
+        let filters = self.filters.clone().unwrap_or_default();
+        let latency_ms = self.started.elapsed().as_millis() as i64;
+
+        actix_web::rt::spawn(async move {
+            let result = services
+                .postgress
+                .query(
+                    r#"
+                    INSERT INTO search_events (id, endpoint, query, normalized_query, filters, result_count, latency_ms)
+                    VALUES ($1::uuid, $2, $3, $4, NULLIF($5, ''), $6::bigint, $7::bigint)
+                    RETURNING *
+                    "#,
+                )
+                .bind(id)
+                .bind(endpoint)
+                .bind(&query)
+                .bind(normalize_query(&query))
+                .bind(filters)
+                .bind(result_count)
+                .bind(latency_ms)
+                .fetch_one::<SearchEvent>()
+                .await;
+
+            if let Err(e) = result {
+                eprintln!("⚠ Failed to record search event: {}", e);
+            }
+        });
+
+        id.to_string()
+    }
+}
+
+/// Only the first click of a search counts, so CTR stays a per-search ratio.
+pub async fn record_click(
+    services: &AppServices,
+    search_id: Uuid,
+    result_id: &str,
+    position: Option<i32>,
+) -> Result<SearchEvent, ApiError> {
+    let updated: Option<SearchEvent> = services
+        .postgress
+        .query(
+            r#"
+            UPDATE search_events
+            SET clicked_result_id = $2,
+                clicked_position = NULLIF($3, '')::integer,
+                clicked_at = CURRENT_TIMESTAMP
+            WHERE id = $1::uuid AND clicked_at IS NULL
+            RETURNING *
+            "#,
+        )
+        .bind(search_id)
+        .bind(result_id)
+        .bind(position.map(|p| p.to_string()).unwrap_or_default())
+        .fetch_optional()
+        .await?;
+
+    match updated {
+        Some(event) => Ok(event),
+        None => services
+            .postgress
+            .query("SELECT * FROM search_events WHERE id = $1::uuid")
+            .bind(search_id)
+            .fetch_optional::<SearchEvent>()
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Search {} not found", search_id))),
+    }
+}
+
+/// Grouped by endpoint as well, so personalized and plain search report side by side.
+const QUERY_STATS: &str = r#"
+    SELECT COALESCE(NULLIF(normalized_query, ''), filters, '') AS query,
+           endpoint,
+           COUNT(*) AS searches,
+           COUNT(*) FILTER (WHERE result_count = 0) AS zero_result_searches,
+           AVG(result_count)::float8 AS avg_results,
+           AVG(latency_ms)::float8 AS avg_latency_ms,
+           COUNT(clicked_at) AS clicks,
+           (COUNT(clicked_at)::float8 / COUNT(*)) AS click_through_rate
+    FROM search_events
+    WHERE created_at >= CURRENT_TIMESTAMP - ($1::integer * INTERVAL '1 day')
+      AND COALESCE(NULLIF(normalized_query, ''), filters, '') <> ''
+    GROUP BY 1, 2
+    HAVING COUNT(*) >= $2::bigint
+"#;
+
+pub async fn top_queries(services: &AppServices, days: i64, limit: i64) -> Result<Vec<QueryStat>, ApiError> {
+    services
+        .postgress
+        .query(format!("{} ORDER BY searches DESC, query, endpoint LIMIT $3::bigint", QUERY_STATS))
+        .bind(days)
+        .bind(1)
+        .bind(limit)
+        .fetch_all()
+        .await
+}
+
+/// Worst-converting queries first, so results nobody clicks on stand out.
+pub async fn click_through_rates(
+    services: &AppServices,
+    days: i64,
+    limit: i64,
+    min_searches: i64,
+) -> Result<Vec<QueryStat>, ApiError> {
+    services
+        .postgress
+        .query(format!(
+            "{} ORDER BY click_through_rate ASC, searches DESC LIMIT $3::bigint",
+            QUERY_STATS
+        ))
+        .bind(days)
+        .bind(min_searches)
+        .bind(limit)
+        .fetch_all()
+        .await
+}
+
+pub async fn zero_result_queries(
+    services: &AppServices,
+    days: i64,
+    limit: i64,
+) -> Result<Vec<ZeroResultQuery>, ApiError> {
+    services
+        .postgress
+        .query(
+            r#"
+            SELECT COALESCE(NULLIF(normalized_query, ''), filters, '') AS query,
+                   endpoint,
+                   COUNT(*) AS searches,
+                   MAX(created_at) AS last_searched_at
+            FROM search_events
+            WHERE created_at >= CURRENT_TIMESTAMP - ($1::integer * INTERVAL '1 day')
+              AND result_count = 0
+              AND COALESCE(NULLIF(normalized_query, ''), filters, '') <> ''
+            GROUP BY 1, 2
+            ORDER BY searches DESC, last_searched_at DESC
+            LIMIT $2::bigint
+            "#,
+        )
+        .bind(days)
+        .bind(limit)
+        .fetch_all()
+        .await
+}
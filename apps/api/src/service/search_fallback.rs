@@ -0,0 +1,210 @@
+use neo4rs::{BoltType, Row};
+
+use crate::error::ApiError;
+use crate::models::programe::Course;
+use crate::models::search::{FilterCondition, FILTERABLE_FIELDS};
+use crate::models::search_query::ParsedQuery;
+use crate::service::AppServices;
+
+const LIST_FIELDS: [&str; 2] = ["prerequisites", "documents"];
+const CASE_INSENSITIVE_FIELDS: [&str; 4] = ["status", "category", "level", "instructor"];
+const SEARCHABLE_TEXT: &str = "toLower(coalesce(c.title, '') + ' ' + coalesce(c.description, '') + ' ' \
+    + coalesce(c.category, '') + ' ' + coalesce(c.instructor, ''))";
+
+/// Compiles filter trees into a Cypher predicate over `c`, collecting parameters as it goes.
+#[derive(Default)]
+struct CypherFilter {
+    params: Vec<(String, BoltType)>,
+}
+
+impl CypherFilter {
+    fn param(&mut self, value: impl Into<BoltType>) -> String {
+        let name = format!("p{}", self.params.len());
+        self.params.push((name.clone(), value.into()));
+        format!("${}", name)
+    }
+
+    /// Applies `predicate` to the property, or to any element of it for list properties.
+    fn on_field(&self, field: &str, predicate: impl Fn(&str) -> String) -> Result<String, ApiError> {
+        if !FILTERABLE_FIELDS.contains(&field) {
+            return Err(ApiError::BadRequest(format!("cannot filter on unknown field '{}'", field)));
+        }
+        let property = format!("c.{}", field.trim_end_matches(".keyword"));
+
+        Ok(if LIST_FIELDS.contains(&field) {
+            format!("any(v IN coalesce({}, []) WHERE {})", property, predicate("v"))
+        } else {
+            predicate(&property)
+        })
+    }
+
+    fn condition(&mut self, condition: &FilterCondition) -> Result<String, ApiError> {
+        match condition {
+            FilterCondition::Term { field, value } => {
+                if CASE_INSENSITIVE_FIELDS.contains(&field.as_str()) {
+                    let p = self.param(value.to_lowercase());
+                    self.on_field(field, |e| format!("toLower(toString({})) = {}", e, p))
+                } else {
+                    let p = self.param(value.clone());
+                    self.on_field(field, |e| format!("toString({}) = {}", e, p))
+                }
+            }
+            FilterCondition::Terms { field, values } => {
+                let p = self.param(values.clone());
+                self.on_field(field, |e| format!("toString({}) IN {}", e, p))
+            }
+            FilterCondition::Range { field, gte, lte, gt, lt } => {
+                let bounds: Vec<(&str, i64)> = [(">=", *gte), ("<=", *lte), (">", *gt), ("<", *lt)]
+                    .into_iter()
+                    .filter_map(|(op, bound)| bound.map(|b| (op, b)))
+                    .collect();
+                self.range(field, "toInteger", bounds)
+            }
+            FilterCondition::FloatRange { field, gte, lte, gt, lt } => {
+                let bounds: Vec<(&str, f64)> = [(">=", *gte), ("<=", *lte), (">", *gt), ("<", *lt)]
+                    .into_iter()
+                    .filter_map(|(op, bound)| bound.map(|b| (op, b)))
+                    .collect();
+                self.range(field, "toFloat", bounds)
+            }
+            FilterCondition::Match { field, value } => {
+                let words: Vec<String> = value.split_whitespace().map(|w| w.to_lowercase()).collect();
+                let p = self.param(words);
+                self.on_field(field, |e| format!("any(w IN {} WHERE toLower(toString({})) CONTAINS w)", p, e))
+            }
+            FilterCondition::Exists { field } => self.on_field(field, |e| format!("{} IS NOT NULL", e)),
+            FilterCondition::Prefix { field, value } => {
+                let p = self.param(value.to_lowercase());
+                self.on_field(field, |e| format!("toLower(toString({})) STARTS WITH {}", e, p))
+            }
+            FilterCondition::Wildcard { field, value } => {
+                let p = self.param(wildcard_regex(value));
+                self.on_field(field, |e| format!("toString({}) =~ {}", e, p))
+            }
+            FilterCondition::And { conditions } => self.combine(conditions, " AND "),
+            FilterCondition::Or { conditions } => self.combine(conditions, " OR "),
+            FilterCondition::Not { condition } => Ok(format!("NOT ({})", self.condition(condition)?)),
+        }
+    }
+
+    /// Compares through `cast`, since older nodes may hold numbers as strings.
+    fn range<T: Into<BoltType>>(&mut self, field: &str, cast: &str, bounds: Vec<(&str, T)>) -> Result<String, ApiError> {
+        if bounds.is_empty() {
+            return Ok("true".to_string());
+        }
+        let comparisons: Vec<(&str, String)> = bounds
+            .into_iter()
+            .map(|(op, bound)| (op, self.param(bound)))
+            .collect();
+        self.on_field(field, |e| {
+            comparisons
+                .iter()
+                .map(|(op, p)| format!("{}({}) {} {}", cast, e, op, p))
+                .collect::<Vec<_>>()
+                .join(" AND ")
+        })
+    }
+
+    fn combine(&mut self, conditions: &[FilterCondition], separator: &str) -> Result<String, ApiError> {
+        let clauses = conditions
+            .iter()
+            .map(|c| self.condition(c).map(|clause| format!("({})", clause)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(clauses.join(separator))
+    }
+}
+
+/// OpenSearch wildcards (`*`, `?`) as a case-insensitive Cypher regex.
+fn wildcard_regex(pattern: &str) -> String {
+    let mut regex = String::from("(?i)");
+    for c in pattern.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            c if c.is_alphanumeric() || c == ' ' => regex.push(c),
+            c => {
+                regex.push('\\');
+                regex.push(c);
+            }
+        }
+    }
+    regex
+}
+
+struct MatchCount(i64);
+
+impl TryFrom<Row> for MatchCount {
+    type Error = ApiError;
+
+    fn try_from(row: Row) -> Result<Self, Self::Error> {
+        row.get("total")
+            .map(MatchCount)
+            .map_err(|_| ApiError::Internal("Could not find key total".to_string()))
+    }
+}
+
+/// Substring search over courses in Neo4j for when OpenSearch is unreachable.
+/// Every word and phrase must appear in the title, description, category or instructor;
+/// filters apply as they would in OpenSearch, but there is no stemming, fuzziness or
+/// document and module matching, and courses with the query in their title rank first.
+pub async fn search_courses(
+    services: &AppServices,
+    parsed: &ParsedQuery,
+    from: i64,
+    size: i64,
+) -> Result<(i64, Vec<Course>), ApiError> {
+    let mut filter = CypherFilter::default();
+
+    let terms: Vec<String> = parsed
+        .text
+        .split_whitespace()
+        .map(|w| w.to_lowercase())
+        .chain(parsed.phrases.iter().map(|p| p.to_lowercase()))
+        .collect();
+    let excluded: Vec<String> = parsed.excluded.iter().map(|e| e.to_lowercase()).collect();
+
+    let terms_param = filter.param(terms);
+    let mut clauses = vec![
+        format!("all(t IN {} WHERE {} CONTAINS t)", terms_param, SEARCHABLE_TEXT),
+        format!("none(t IN {} WHERE {} CONTAINS t)", filter.param(excluded), SEARCHABLE_TEXT),
+    ];
+    for condition in &parsed.filters {
+        clauses.push(format!("({})", filter.condition(condition)?));
+    }
+    for condition in &parsed.excluded_filters {
+        clauses.push(format!("NOT ({})", filter.condition(condition)?));
+    }
+    let predicate = clauses.join(" AND ");
+
+    let with_params = |cypher: String| {
+        filter
+            .params
+            .iter()
+            .fold(services.neo4j.query_nodes(cypher), |query, (name, value)| {
+                query.param(name, value.clone())
+            })
+    };
+
+    let total = with_params(format!("MATCH (c:Course) WHERE {} RETURN count(c) AS total", predicate))
+        .fetch_all::<MatchCount>()
+        .await?
+        .first()
+        .map(|count| count.0)
+        .unwrap_or(0);
+
+    let courses = with_params(format!(
+        "MATCH (c:Course) WHERE {}
+         RETURN c
+         ORDER BY size([t IN {} WHERE toLower(coalesce(c.title, '')) CONTAINS t]) DESC,
+                  coalesce(c.featured, false) DESC, coalesce(c.rating, 0) DESC, c.title, c.id
+         SKIP $skip LIMIT $limit",
+        predicate, terms_param
+    ))
+    .param("skip", from)
+    .param("limit", size)
+    .fetch_key("c")
+    .fetch::<Course>()
+    .await?;
+
+    Ok((total, courses))
+}
@@ -0,0 +1,178 @@
+use crate::error::ApiError;
+use crate::models::video::VideoMetadata;
+use crate::traits::object_storage::ObjectStorage;
+
+const BOX_HEADER_LEN: i64 = 16;
+const MAX_TOP_LEVEL_BOXES: usize = 64;
+const MAX_MOOV_BYTES: i64 = 64 * 1024 * 1024;
+
+struct Mp4Box<'a> {
+    kind: [u8; 4],
+    body: &'a [u8],
+}
+
+fn read_u16(data: &[u8], at: usize) -> Option<u16> {
+    data.get(at..at + 2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+}
+
+fn read_u32(data: &[u8], at: usize) -> Option<u32> {
+    data.get(at..at + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn read_u64(data: &[u8], at: usize) -> Option<u64> {
+    data.get(at..at + 8).map(|b| u64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+}
+
+/// Parses a box header, returning (type, header length, total box length).
+fn box_header(data: &[u8], remaining: u64) -> Option<([u8; 4], usize, u64)> {
+    let size = read_u32(data, 0)? as u64;
+    let kind: [u8; 4] = data.get(4..8)?.try_into().ok()?;
+
+    match size {
+        0 => Some((kind, 8, remaining)),
+        1 => Some((kind, 16, read_u64(data, 8)?)),
+        n if n >= 8 => Some((kind, 8, n)),
+        _ => None,
+    }
+}
+
+fn children(data: &[u8]) -> Vec<Mp4Box<'_>> {
+    let mut boxes = Vec::new();
+    let mut offset = 0usize;
+
+    while offset + 8 <= data.len() {
+        let rest = &data[offset..];
+        let Some((kind, header_len, total)) = box_header(rest, rest.len() as u64) else {
+            break;
+        };
+        if total < header_len as u64 || total > rest.len() as u64 {
+            break;
+        }
+
+        boxes.push(Mp4Box {
+            kind,
+            body: &rest[header_len..total as usize],
+        });
+        offset += total as usize;
+    }
+
+    boxes
+}
+
+fn child<'a>(data: &'a [u8], kind: &[u8; 4]) -> Option<&'a [u8]> {
+    children(data).into_iter().find(|b| &b.kind == kind).map(|b| b.body)
+}
+
+fn path<'a>(data: &'a [u8], kinds: &[&[u8; 4]]) -> Option<&'a [u8]> {
+    kinds.iter().try_fold(data, |current, kind| child(current, kind))
+}
+
+fn movie_duration(mvhd: &[u8]) -> Option<f64> {
+    let (timescale, duration) = match mvhd.first()? {
+        1 => (read_u32(mvhd, 20)?, read_u64(mvhd, 24)?),
+        _ => (read_u32(mvhd, 12)?, read_u32(mvhd, 16)? as u64),
+    };
+
+    if timescale == 0 || duration == u64::MAX || duration == u32::MAX as u64 {
+        return None;
+    }
+
+    Some(duration as f64 / timescale as f64)
+}
+
+fn track_dimensions(tkhd: &[u8]) -> Option<(i64, i64)> {
+    let matrix_end = match tkhd.first()? {
+        1 => 4 + 32 + 8 + 8 + 36,
+        _ => 4 + 20 + 8 + 8 + 36,
+    };
+
+    let width = read_u16(tkhd, matrix_end)? as i64;
+    let height = read_u16(tkhd, matrix_end + 4)? as i64;
+    Some((width, height))
+}
+
+fn codec_name(fourcc: &[u8]) -> String {
+    match fourcc {
+        b"avc1" | b"avc3" => "h264".to_string(),
+        b"hvc1" | b"hev1" => "hevc".to_string(),
+        b"av01" => "av1".to_string(),
+        b"vp09" => "vp9".to_string(),
+        b"mp4v" => "mpeg4".to_string(),
+        b"apcn" | b"apch" | b"apcs" | b"apco" | b"ap4h" => "prores".to_string(),
+        other => String::from_utf8_lossy(other).trim().to_string(),
+    }
+}
+
+/// Extracts duration, resolution and codec from the payload of a `moov` box.
+pub fn parse_moov(moov: &[u8]) -> Option<VideoMetadata> {
+    let duration_seconds = movie_duration(child(moov, b"mvhd")?)?;
+
+    let video_track = children(moov)
+        .into_iter()
+        .filter(|b| &b.kind == b"trak")
+        .find(|trak| {
+            path(trak.body, &[b"mdia", b"hdlr"])
+                .and_then(|hdlr| hdlr.get(8..12))
+                .map(|handler| handler == b"vide")
+                .unwrap_or(false)
+        })?;
+
+    let (width, height) = child(video_track.body, b"tkhd").and_then(track_dimensions)?;
+
+    let codec = path(video_track.body, &[b"mdia", b"minf", b"stbl", b"stsd"])
+        .and_then(|stsd| stsd.get(12..16))
+        .map(codec_name)
+        .unwrap_or_else(|| "unknown".to_string());
+
+    Some(VideoMetadata {
+        duration_seconds,
+        width,
+        height,
+        codec,
+    })
+}
+
+/// Walks the top-level boxes of an MP4/MOV object with ranged reads so only
+/// the `moov` box is downloaded, wherever it sits in the file.
+pub async fn probe_video(storage: &dyn ObjectStorage, bucket: &str, key: &str) -> Result<VideoMetadata, ApiError> {
+    let size = storage
+        .head(bucket, key)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Object {}/{} not found", bucket, key)))?
+        .size;
+
+    let mut offset: i64 = 0;
+
+    for _ in 0..MAX_TOP_LEVEL_BOXES {
+        if offset + 8 > size {
+            break;
+        }
+
+        let end = (offset + BOX_HEADER_LEN).min(size) - 1;
+        let header = read_range(storage, bucket, key, offset, end).await?;
+        let (kind, header_len, total) = box_header(&header, (size - offset) as u64)
+            .ok_or_else(|| ApiError::BadRequest("Video is not a valid MP4/MOV container".to_string()))?;
+
+        if &kind == b"moov" {
+            if total as i64 > MAX_MOOV_BYTES {
+                return Err(ApiError::BadRequest("Video metadata box is too large".to_string()));
+            }
+
+            let moov = read_range(storage, bucket, key, offset + header_len as i64, offset + total as i64 - 1).await?;
+            return parse_moov(&moov)
+                .ok_or_else(|| ApiError::BadRequest("Video has no readable video track".to_string()));
+        }
+
+        offset += total as i64;
+    }
+
+    Err(ApiError::BadRequest("Video has no moov box".to_string()))
+}
+
+async fn read_range(storage: &dyn ObjectStorage, bucket: &str, key: &str, start: i64, end: i64) -> Result<Vec<u8>, ApiError> {
+    use futures::TryStreamExt;
+
+    let object = storage.get(bucket, key, Some((start, end))).await?;
+    let chunks: Vec<bytes::Bytes> = object.body.try_collect().await?;
+    Ok(chunks.concat())
+}
@@ -1,3 +1,5 @@
 pub mod from_node;
 pub mod pg_model_trait;
 pub mod redis_trait;
+pub mod notification_channel;
+pub mod object_storage;
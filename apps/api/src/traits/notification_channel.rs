@@ -0,0 +1,9 @@
+use async_trait::async_trait;
+use crate::{error::ApiError, models::{auth::User, notification::Notification}};
+
+#[async_trait]
+pub trait NotificationChannel: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn deliver(&self, user: &User, notification: &Notification) -> Result<(), ApiError>;
+}
@@ -0,0 +1,130 @@
+use std::time::Duration;
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
+use crate::error::ApiError;
+
+pub type ByteChunkStream = BoxStream<'static, Result<Bytes, ApiError>>;
+
+pub struct ObjectHead {
+    pub size: i64,
+    pub content_type: Option<String>,
+    pub e_tag: Option<String>,
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+pub struct ObjectStream {
+    pub content_type: Option<String>,
+    pub content_length: i64,
+    pub body: ByteChunkStream,
+}
+
+pub struct ObjectSummary {
+    pub key: String,
+    pub size: i64,
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+pub struct ObjectPage {
+    pub objects: Vec<ObjectSummary>,
+    pub next_token: Option<String>,
+}
+
+pub struct UploadedPart {
+    pub part_number: i32,
+    pub e_tag: String,
+    pub size: i64,
+}
+
+#[async_trait]
+pub trait ObjectStorage: Send + Sync {
+    async fn create_bucket(&self, bucket: &str) -> Result<(), ApiError>;
+
+    async fn put(&self, bucket: &str, key: &str, data: Vec<u8>, content_type: Option<&str>) -> Result<(), ApiError>;
+
+    async fn get(&self, bucket: &str, key: &str, range: Option<(i64, i64)>) -> Result<ObjectStream, ApiError>;
+
+    async fn head(&self, bucket: &str, key: &str) -> Result<Option<ObjectHead>, ApiError>;
+
+    async fn delete(&self, bucket: &str, key: &str) -> Result<(), ApiError>;
+
+    async fn list(
+        &self,
+        bucket: &str,
+        prefix: Option<&str>,
+        continuation_token: Option<String>,
+    ) -> Result<ObjectPage, ApiError>;
+
+    async fn presign_get(&self, bucket: &str, key: &str, expires_in: Duration) -> Result<String, ApiError>;
+
+    async fn presign_put(
+        &self,
+        bucket: &str,
+        key: &str,
+        content_type: &str,
+        content_length: i64,
+        expires_in: Duration,
+    ) -> Result<String, ApiError>;
+
+    async fn create_multipart_upload(&self, bucket: &str, key: &str, content_type: &str) -> Result<String, ApiError>;
+
+    async fn presign_upload_part(
+        &self,
+        bucket: &str,
+        key: &str,
+        upload_id: &str,
+        part_number: i32,
+        expires_in: Duration,
+    ) -> Result<String, ApiError>;
+
+    async fn upload_part(
+        &self,
+        bucket: &str,
+        key: &str,
+        upload_id: &str,
+        part_number: i32,
+        data: Vec<u8>,
+    ) -> Result<String, ApiError>;
+
+    async fn list_parts(&self, bucket: &str, key: &str, upload_id: &str) -> Result<Vec<UploadedPart>, ApiError>;
+
+    async fn complete_multipart_upload(
+        &self,
+        bucket: &str,
+        key: &str,
+        upload_id: &str,
+        parts: &[UploadedPart],
+    ) -> Result<(), ApiError>;
+
+    async fn abort_multipart_upload(&self, bucket: &str, key: &str, upload_id: &str) -> Result<(), ApiError>;
+
+    async fn object_exists(&self, bucket: &str, key: &str) -> Result<bool, ApiError> {
+        Ok(self.head(bucket, key).await?.is_some())
+    }
+
+    async fn download(&self, bucket: &str, key: &str) -> Result<Vec<u8>, ApiError> {
+        use futures::TryStreamExt;
+
+        let object = self.get(bucket, key, None).await?;
+        let chunks: Vec<Bytes> = object.body.try_collect().await?;
+        Ok(chunks.concat())
+    }
+
+    async fn list_all(&self, bucket: &str, prefix: Option<&str>) -> Result<Vec<ObjectSummary>, ApiError> {
+        let mut objects = Vec::new();
+        let mut token = None;
+
+        loop {
+            let page = self.list(bucket, prefix, token).await?;
+            objects.extend(page.objects);
+
+            match page.next_token {
+                Some(next) => token = Some(next),
+                None => break,
+            }
+        }
+
+        Ok(objects)
+    }
+}
@@ -15,6 +15,8 @@ pub trait RedisCache: Send + Sync {
     async fn delete(&self, key: &str) -> Result<(), ApiError>;
 
     async fn delete_all(&self, pattern: &str) -> Result<(), ApiError>;
+
+    async fn increment(&self, key: &str, ttl_seconds: u64) -> Result<i64, ApiError>;
 }
 
 
@@ -0,0 +1,57 @@
+use actix_web::web;
+use crate::{error::ApiError, models::programe::Course, service::{document_text::index_course_documents, AppServices}};
+
+async fn load_course(services: &AppServices, course_id: &str) -> Result<Option<Course>, ApiError> {
+    let courses: Vec<Course> = services.neo4j
+        .query_nodes("MATCH (c:Course {id: $id}) RETURN c")
+        .param("id", course_id.to_string())
+        .fetch_key("c")
+        .fetch()
+        .await?;
+
+    Ok(courses.into_iter().next())
+}
+
+/// Re-extracts a course's documents in the background after they change.
+pub fn schedule_course_documents(services: &web::Data<AppServices>, course_id: &str) {
+    let services = services.clone();
+    let course_id = course_id.to_string();
+
+    actix_web::rt::spawn(async move {
+        let result = match load_course(&services, &course_id).await {
+            Ok(Some(course)) => index_course_documents(&services, &course).await.map(|_| ()),
+            Ok(None) => services.opensearch.delete_course_documents(&course_id).await,
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = result {
+            eprintln!("⚠ Document indexing failed for course {}: {}", course_id, e);
+        }
+    });
+}
+
+/// Re-extracts and re-indexes the documents of every course in the catalog.
+pub async fn reindex_all_documents(services: web::Data<AppServices>) {
+    let courses: Vec<Course> = match services.neo4j
+        .query_nodes("MATCH (c:Course) RETURN c")
+        .fetch_key("c")
+        .fetch()
+        .await
+    {
+        Ok(courses) => courses,
+        Err(e) => {
+            eprintln!("⚠ Document reindex could not load courses: {}", e);
+            return;
+        }
+    };
+
+    let mut pages = 0;
+    for course in &courses {
+        match index_course_documents(&services, course).await {
+            Ok(n) => pages += n,
+            Err(e) => eprintln!("⚠ Document indexing failed for course {}: {}", course.id, e),
+        }
+    }
+
+    println!("Indexed {} document pages across {} courses", pages, courses.len());
+}
@@ -0,0 +1,4 @@
+pub mod notifications;
+pub mod uploads;
+pub mod documents;
+pub mod search_index;
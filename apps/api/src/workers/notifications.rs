@@ -0,0 +1,190 @@
+use std::{sync::Arc, time::Duration};
+use actix_web::web;
+use rdkafka::{consumer::{Consumer, StreamConsumer}, Message};
+use crate::{
+    dtos::{events::CacheInvalidationEvent, notifications::NewNotification},
+    error::ApiError,
+    models::{auth::User, notification::UpcomingDeadline, programe::{Course, Module}},
+    service::AppServices,
+};
+
+const DEADLINE_SCAN_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+async fn enrolled_users(services: &AppServices, course_id: &str) -> Result<Vec<User>, ApiError> {
+    services
+        .postgress
+        .query(
+            r#"
+            SELECT u.*
+            FROM users u
+            JOIN enrollments e ON e.user_id = u.id
+            WHERE e.course_id = $1
+            "#,
+        )
+        .bind(course_id)
+        .fetch_all()
+        .await
+}
+
+async fn owning_course(services: &AppServices, cypher: &str, resource_id: &str) -> Result<Option<Course>, ApiError> {
+    let courses: Vec<Course> = services.neo4j
+        .query_nodes(cypher)
+        .param("id", resource_id.to_string())
+        .fetch_key("c")
+        .fetch()
+        .await?;
+
+    Ok(courses.into_iter().next())
+}
+
+async fn handle_event(services: &AppServices, event: CacheInvalidationEvent) -> Result<(), ApiError> {
+    let (cypher, title) = match event.event_type.as_str() {
+        // Lessons are created on their own and only reach a course once a module links them.
+        "lesson_attached" => (
+            "MATCH (c:Course)-[:HAS_MODULE]->(:Module)-[:HAS_LESSON]->(:Lesson {id: $id}) RETURN DISTINCT c",
+            "New lesson available",
+        ),
+        "module_created" | "module_updated" => (
+            "MATCH (c:Course)-[:HAS_MODULE]->(:Module {id: $id}) RETURN DISTINCT c",
+            "Course content updated",
+        ),
+        "course_updated" => (
+            "MATCH (c:Course {id: $id}) RETURN c",
+            "Course updated",
+        ),
+        _ => return Ok(()),
+    };
+
+    let Some(course) = owning_course(services, cypher, &event.resource_id).await? else {
+        return Ok(());
+    };
+
+    for user in enrolled_users(services, &course.id).await? {
+        services.notifications
+            .notify(
+                &services.postgress,
+                &user,
+                NewNotification {
+                    kind: event.event_type.clone(),
+                    title: title.to_string(),
+                    body: format!("{} has new or updated content.", course.title),
+                    resource_id: Some(event.resource_id.clone()),
+                    dedup_key: Some(format!(
+                        "{}:{}:{}:{}",
+                        event.event_type, event.resource_id, event.timestamp, user.id
+                    )),
+                },
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+async fn notify_upcoming_deadlines(services: &AppServices) -> Result<(), ApiError> {
+    let deadlines: Vec<UpcomingDeadline> = services
+        .postgress
+        .query(
+            r#"
+            SELECT m.user_id, s.id AS schedule_id, s.module_id, c.name AS cohort_name, s.due_at
+            FROM cohort_module_schedules s
+            JOIN cohorts c ON c.id = s.cohort_id
+            JOIN cohort_members m ON m.cohort_id = s.cohort_id
+            WHERE m.status = 'active'
+              AND s.due_at IS NOT NULL
+              AND s.due_at > NOW() AT TIME ZONE 'UTC'
+              AND s.due_at <= (NOW() AT TIME ZONE 'UTC') + INTERVAL '24 hours'
+            "#,
+        )
+        .fetch_all()
+        .await?;
+
+    for deadline in deadlines {
+        let Some(user) = services
+            .postgress
+            .query("SELECT * FROM users WHERE id = $1::uuid")
+            .bind(deadline.user_id)
+            .fetch_optional::<User>()
+            .await?
+        else {
+            continue;
+        };
+
+        let modules: Vec<Module> = services.neo4j
+            .query_nodes("MATCH (m:Module {id: $id}) RETURN m")
+            .param("id", deadline.module_id.clone())
+            .fetch_key("m")
+            .fetch()
+            .await?;
+        let module_title = modules
+            .first()
+            .map(|m| m.title.clone())
+            .unwrap_or_else(|| deadline.module_id.clone());
+
+        services.notifications
+            .notify(
+                &services.postgress,
+                &user,
+                NewNotification {
+                    kind: "deadline_upcoming".to_string(),
+                    title: format!("{} is due soon", module_title),
+                    body: format!(
+                        "{} for {} is due on {} UTC.",
+                        module_title,
+                        deadline.cohort_name,
+                        deadline.due_at.format("%Y-%m-%d %H:%M")
+                    ),
+                    resource_id: Some(deadline.module_id.clone()),
+                    dedup_key: Some(format!("deadline:{}:{}", deadline.schedule_id, user.id)),
+                },
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn run_event_consumer(services: web::Data<AppServices>, consumer: Arc<StreamConsumer>) {
+    if let Err(e) = consumer.subscribe(&["cache-invalidation"]) {
+        eprintln!("⚠ Notification consumer could not subscribe: {}", e);
+        return;
+    }
+
+    loop {
+        let message = match consumer.recv().await {
+            Ok(m) => m,
+            Err(e) => {
+                eprintln!("⚠ Kafka receive error: {}", e);
+                continue;
+            }
+        };
+
+        let Some(Ok(payload)) = message.payload_view::<str>() else {
+            continue;
+        };
+
+        let event: CacheInvalidationEvent = match serde_json::from_str(payload) {
+            Ok(e) => e,
+            Err(e) => {
+                eprintln!("⚠ Skipping malformed event: {}", e);
+                continue;
+            }
+        };
+
+        if let Err(e) = handle_event(&services, event).await {
+            eprintln!("⚠ Failed to create notifications: {}", e);
+        }
+    }
+}
+
+pub async fn run_deadline_reminders(services: web::Data<AppServices>) {
+    let mut interval = tokio::time::interval(DEADLINE_SCAN_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = notify_upcoming_deadlines(&services).await {
+            eprintln!("⚠ Deadline reminder scan failed: {}", e);
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use std::time::Duration;
+use actix_web::web;
+use crate::service::{index_queue, programs_reindex::programs_index_status, AppServices};
+
+const RETRY_INTERVAL: Duration = Duration::from_secs(30);
+const RETRY_BATCH_SIZE: i64 = 100;
+
+/// Warns at startup when the live programs index was built from an older mapping.
+pub async fn check_programs_mapping(services: web::Data<AppServices>) {
+    match programs_index_status(&services.opensearch).await {
+        Ok(status) if status.reindex_required => eprintln!(
+            "⚠ {} mapping is out of date ({} has checksum {}, expected {}); run the reindex command",
+            status.alias,
+            status.index.unwrap_or_default(),
+            status.checksum.unwrap_or_else(|| "none".to_string()),
+            status.expected_checksum
+        ),
+        Ok(_) => {}
+        Err(e) => eprintln!("⚠ Could not check the programs index mapping: {}", e),
+    }
+}
+
+/// Replays index writes that failed while OpenSearch was unavailable.
+pub async fn run_index_retries(services: web::Data<AppServices>) {
+    let mut interval = tokio::time::interval(RETRY_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        match index_queue::retry_due(&services, RETRY_BATCH_SIZE).await {
+            Ok(0) => {}
+            Ok(n) => println!("Reindexed {} queued search entries", n),
+            Err(e) => eprintln!("⚠ Search index retry failed: {}", e),
+        }
+    }
+}
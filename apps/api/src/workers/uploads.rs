@@ -0,0 +1,57 @@
+use std::time::Duration;
+use actix_web::web;
+use crate::{error::ApiError, models::upload::UploadSession, service::AppServices};
+
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const STALE_AFTER_HOURS: i64 = 24;
+
+async fn abort_stale_uploads(services: &AppServices) -> Result<usize, ApiError> {
+    let stale: Vec<UploadSession> = services
+        .postgress
+        .query(
+            r#"
+            SELECT * FROM upload_sessions
+            WHERE status = 'pending'
+              AND updated_at < CURRENT_TIMESTAMP - ($1::bigint * INTERVAL '1 hour')
+            "#,
+        )
+        .bind(STALE_AFTER_HOURS)
+        .fetch_all()
+        .await?;
+
+    let mut aborted = 0;
+    for session in stale {
+        if let Err(e) = services.storage
+            .abort_multipart_upload(&session.bucket, &session.object_key, &session.s3_upload_id)
+            .await
+        {
+            // Still pending, so the next sweep retries instead of orphaning the parts.
+            eprintln!("⚠ Could not abort upload {}, retrying next sweep: {}", session.id, e);
+            continue;
+        }
+
+        let _: UploadSession = services
+            .postgress
+            .query("UPDATE upload_sessions SET status = 'expired', updated_at = CURRENT_TIMESTAMP WHERE id = $1::uuid RETURNING *")
+            .bind(session.id)
+            .fetch_one()
+            .await?;
+        aborted += 1;
+    }
+
+    Ok(aborted)
+}
+
+pub async fn run_upload_cleanup(services: web::Data<AppServices>) {
+    let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        match abort_stale_uploads(&services).await {
+            Ok(0) => {}
+            Ok(n) => println!("Aborted {} stale multipart uploads", n),
+            Err(e) => eprintln!("⚠ Upload cleanup failed: {}", e),
+        }
+    }
+}